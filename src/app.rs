@@ -1,35 +1,137 @@
+use ratatui::layout::Rect;
 use ratatui::widgets::ListState;
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::mpsc::Sender;
 use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use crate::channel::{self, ChannelFilters, Preview};
+use crate::download::DownloadedFile;
+use crate::events::AppEvent;
+use crate::history::{HistoryView, Outcome};
+use crate::input::TextInput;
+use crate::job::Job;
+use crate::keymap::{Action, Keymap};
+use crate::resume::InterruptedJob;
+use crate::subscriptions::Subscription;
+use crate::theme::Theme;
+use crate::url::{self, UrlKind, YoutubeUrl};
 
-#[derive(PartialEq)]
+#[derive(PartialEq, Debug)]
 pub enum AppStep {
+    Resume,
     SelectType,
     EnterUrl,
+    PlaylistChoice,
+    ChannelFilters,
     SelectFormat,
     Confirm,
     Downloading,
     Complete,
+    History,
+    Subscriptions,
+    BatchImport,
 }
 
 pub struct AppState {
     pub step: AppStep,
     pub download_type: Option<usize>,
     pub url: String,
+    pub url_input: TextInput,
+    pub url_error: Option<String>,
+    /// The entered URL while asking whether to download the video or its playlist.
+    pub parsed_url: Option<YoutubeUrl>,
     pub format: Option<usize>,
+    /// Channel filters of the job being set up or run.
+    pub filters: Option<ChannelFilters>,
+    /// Output template, download archive and folder of the job being run, for subscription and imported jobs.
+    pub template: Option<String>,
+    pub archive: Option<PathBuf>,
+    pub output_dir: Option<PathBuf>,
+    /// Filters being edited in the channel step.
+    pub channel_filters: ChannelFilters,
+    /// Value of the channel filter being edited, when `input_mode` is set in that step.
+    pub filter_input: TextInput,
+    pub filter_error: Option<String>,
+    /// Replaced whenever the filters change, so a stale preview can't overwrite a new one.
+    pub channel_preview: Arc<Mutex<Preview>>,
     pub status: String,
     pub list_state: ListState,
     pub input_mode: bool,
+    pub confirm_quit: bool,
+    /// Set when the user chose to quit once the running downloads finish.
+    pub quit_when_done: bool,
     pub download_progress: Arc<Mutex<DownloadProgress>>,
+    pub download_thread: Option<JoinHandle<()>>,
+    pub interrupted: Vec<InterruptedJob>,
+    pub history: HistoryView,
+    pub subscriptions: Vec<Subscription>,
+    /// Path typed on the import screen; when empty, the selected file is imported.
+    pub batch_input: TextInput,
+    pub batch_files: Vec<PathBuf>,
+    /// Invalid lines of the last import attempt.
+    pub batch_errors: Vec<String>,
+    /// Extra URLs pasted alongside `url`, downloaded with the same settings.
+    pub queued_urls: Vec<String>,
+    /// Jobs waiting to run after the current download.
+    pub queue: VecDeque<Job>,
+    /// Jobs finished so far in this run of the queue, with their outcomes.
+    pub results: Vec<(Job, Outcome)>,
+    pub theme: Theme,
+    pub keymap: Keymap,
+    /// Whether the key binding overlay is shown.
+    pub show_help: bool,
+    /// Lines the log panel is scrolled back from the newest line.
+    pub log_scroll: usize,
+    pub click_areas: ClickAreas,
 }
 
+/// Screen regions from the last frame, used to map mouse events to what was drawn there.
+#[derive(Default)]
+pub struct ClickAreas {
+    /// Inner area of the main list and the index of its first visible row.
+    pub list: Option<(Rect, usize)>,
+    pub log: Option<Rect>,
+    pub buttons: Vec<(Rect, Action)>,
+}
+
+#[derive(Default)]
 pub struct DownloadProgress {
     pub active: bool,
     pub message: String,
     pub spinner_index: usize,
+    pub outcome: Option<Outcome>,
+    pub files: Vec<DownloadedFile>,
+    /// Asks the download thread to kill yt-dlp and stop.
+    pub cancel_requested: bool,
+    /// yt-dlp output of the jobs run so far, most recent last.
+    pub log: Vec<String>,
+    /// Wakes the main loop when the progress changes.
+    pub events: Option<Sender<AppEvent>>,
+}
+
+/// Oldest log lines are dropped past this many.
+const MAX_LOG_LINES: usize = 1000;
+
+impl DownloadProgress {
+    pub fn push_log(&mut self, line: String) {
+        if self.log.len() >= MAX_LOG_LINES {
+            self.log.remove(0);
+        }
+        self.log.push(line);
+        self.notify();
+    }
+
+    /// Tells the UI to redraw; call after changing anything it shows.
+    pub fn notify(&self) {
+        if let Some(events) = &self.events {
+            let _ = events.send(AppEvent::Progress);
+        }
+    }
 }
 
 impl AppState {
-    pub fn new() -> Self {
+    pub fn new(theme: Theme, keymap: Keymap) -> Self {
         let mut list_state = ListState::default();
         list_state.select(Some(0));
         
@@ -37,25 +139,128 @@ impl AppState {
             step: AppStep::SelectType,
             download_type: None,
             url: String::new(),
+            url_input: TextInput::new(),
+            url_error: None,
+            parsed_url: None,
             format: None,
+            filters: None,
+            template: None,
+            archive: None,
+            output_dir: None,
+            channel_filters: ChannelFilters::default(),
+            filter_input: TextInput::new(),
+            filter_error: None,
+            channel_preview: Arc::new(Mutex::new(Preview::NotRun)),
             status: "Select download type using arrow keys and Enter".to_string(),
             list_state,
             input_mode: false,
-            download_progress: Arc::new(Mutex::new(DownloadProgress {
-                active: false,
-                message: String::new(),
-                spinner_index: 0,
-            })),
+            confirm_quit: false,
+            quit_when_done: false,
+            download_progress: Arc::new(Mutex::new(DownloadProgress::default())),
+            download_thread: None,
+            interrupted: Vec::new(),
+            history: HistoryView::new(Vec::new()),
+            subscriptions: Vec::new(),
+            batch_input: TextInput::new(),
+            batch_files: Vec::new(),
+            batch_errors: Vec::new(),
+            queued_urls: Vec::new(),
+            queue: VecDeque::new(),
+            results: Vec::new(),
+            theme,
+            keymap,
+            show_help: false,
+            log_scroll: 0,
+            click_areas: ClickAreas::default(),
         }
     }
 
     pub fn reset(&mut self) {
-        *self = Self::new();
+        let url_history = self.url_input.take_history();
+        let events = self.download_progress.lock().ok().and_then(|p| p.events.clone());
+        *self = Self::new(self.theme, self.keymap.clone());
+        self.url_input.set_history(url_history);
+        if let Ok(mut progress) = self.download_progress.lock() {
+            progress.events = events;
+        }
+    }
+
+    /// Summary of the current state, included in crash reports.
+    pub fn describe(&self) -> String {
+        format!(
+            "  step: {:?}\n  download type: {:?}\n  format: {:?}\n  url: {}\n  status: {}\n  queued jobs: {}\n  finished jobs: {}",
+            self.step,
+            self.download_type,
+            self.format,
+            self.url,
+            self.status,
+            self.queue.len(),
+            self.results.len(),
+        )
+    }
+
+    pub fn is_downloading(&self) -> bool {
+        self.step == AppStep::Downloading
+    }
+
+    /// Cancels the running download, if any, and waits until yt-dlp has exited.
+    pub fn stop_download(&mut self) {
+        if let Some(handle) = self.download_thread.take() {
+            if let Ok(mut progress) = self.download_progress.lock() {
+                progress.cancel_requested = true;
+            }
+            let _ = handle.join();
+        }
+    }
+
+    /// Starts over, offering the next interrupted job first if any are left.
+    pub fn next_interrupted(&mut self) {
+        let interrupted = std::mem::take(&mut self.interrupted);
+        self.reset();
+        self.interrupted = interrupted;
+
+        if let Some(pending) = self.interrupted.first() {
+            self.step = AppStep::Resume;
+            let job = pending.job.clone();
+            self.load_job(job);
+            self.status = "A previous download was interrupted. Resume it?".to_string();
+        }
+    }
+
+    pub fn current_job(&self) -> Option<Job> {
+        Some(Job {
+            filters: self.filters.clone(),
+            template: self.template.clone(),
+            archive: self.archive.clone(),
+            output_dir: self.output_dir.clone(),
+            ..Job::new(self.url.clone(), self.download_type?, self.format?)
+        })
+    }
+
+    /// Makes `job` the one `current_job` returns.
+    pub fn load_job(&mut self, job: Job) {
+        self.url = job.url;
+        self.download_type = Some(job.download_type);
+        self.format = Some(job.format);
+        self.filters = job.filters;
+        self.template = job.template;
+        self.archive = job.archive;
+        self.output_dir = job.output_dir;
+    }
+
+    /// Whether the URL being set up is a channel or playlist, which can be subscribed to.
+    pub fn can_subscribe(&self) -> bool {
+        url::parse(&self.url)
+            .is_ok_and(|u| matches!(u.kind, UrlKind::Channel { .. } | UrlKind::Playlist { .. }))
     }
 
     pub fn get_current_options_len(&self) -> usize {
         match self.step {
+            AppStep::Resume => 3,
             AppStep::SelectType => 3,
+            AppStep::PlaylistChoice => 2,
+            // Tab, the filters, then "Preview" and "Continue"
+            AppStep::ChannelFilters => channel::FIELDS.len() + 3,
             AppStep::SelectFormat => {
                 match self.download_type {
                     Some(0) => 3, // Video formats
@@ -64,7 +269,12 @@ impl AppState {
                     _ => 0,
                 }
             }
+            // Start, Cancel, and Subscribe for channels and playlists
+            AppStep::Confirm if self.can_subscribe() => 3,
             AppStep::Confirm => 2,
+            AppStep::History => self.history.visible().len(),
+            AppStep::Subscriptions => self.subscriptions.len(),
+            AppStep::BatchImport => self.batch_files.len(),
             _ => 0,
         }
     }
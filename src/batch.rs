@@ -0,0 +1,78 @@
+use std::path::{Path, PathBuf};
+
+use crate::job::{self, Job};
+use crate::queue;
+use crate::url;
+
+/// Reads a batch file of one URL per line, each optionally prefixed with `type[:format]`
+/// (e.g. `audio:mp3 URL`); other lines use the given type and format.
+///
+/// Blank lines and `#` comments are skipped. Every line is checked, and if any is invalid
+/// all problems are returned, one per line, instead of the jobs. JSON files are read as
+/// exported queues, which carry their own settings.
+pub fn load(path: &Path, download_type: usize, format: usize) -> Result<Vec<Job>, Vec<String>> {
+    if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json")) {
+        return queue::load(path);
+    }
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| vec![format!("Could not read {}: {}", path.display(), e)])?;
+
+    let mut jobs = Vec::new();
+    let mut errors = Vec::new();
+    for (number, line) in contents.lines().enumerate() {
+        // A comment can also follow the URL, separated by whitespace
+        let line = line.split(" #").next().unwrap_or_default().trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        match parse_line(line, download_type, format) {
+            Ok(job) => jobs.push(job),
+            Err(e) => errors.push(format!("Line {}: {}", number + 1, e)),
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+    if jobs.is_empty() {
+        return Err(vec![format!("{} contains no URLs", path.display())]);
+    }
+    Ok(jobs)
+}
+
+fn parse_line(line: &str, download_type: usize, format: usize) -> Result<Job, String> {
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+    let (settings, url) = match tokens[..] {
+        [url] => ((download_type, format), url),
+        [prefix, url] => {
+            let (type_name, format_name) = match prefix.split_once(':') {
+                Some((type_name, format_name)) => (type_name, Some(format_name)),
+                None => (prefix, None),
+            };
+            (job::parse_settings(type_name, format_name)?, url)
+        }
+        _ => return Err("expected a URL, optionally preceded by type[:format]".to_string()),
+    };
+
+    let parsed = url::parse(url)?;
+    Ok(Job::new(parsed.normalized(), settings.0, settings.1))
+}
+
+/// The `.txt` files and exported queues in `dir`, sorted by name, offered by the import screen.
+pub fn import_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok().map(|e| e.path()))
+                .filter(|path| path.is_file() && is_importable(path))
+                .collect()
+        })
+        .unwrap_or_default();
+    files.sort();
+    files
+}
+
+fn is_importable(path: &Path) -> bool {
+    let name = path.file_name().map(|n| n.to_string_lossy().to_lowercase()).unwrap_or_default();
+    name.ends_with(".txt") || name.ends_with(queue::SUFFIX)
+}
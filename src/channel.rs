@@ -0,0 +1,274 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::process::Command;
+
+/// Limits on which videos of a channel get downloaded.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+#[serde(default)]
+pub struct ChannelFilters {
+    /// yt-dlp date, either `YYYYMMDD` or relative like `today-2weeks`.
+    pub date_after: Option<String>,
+    pub date_before: Option<String>,
+    /// Durations in seconds.
+    pub min_duration: Option<u64>,
+    pub max_duration: Option<u64>,
+    /// Python regular expression matched case-insensitively against titles.
+    pub title_regex: Option<String>,
+    pub max_items: Option<u32>,
+}
+
+/// What the last "preview matches" run found.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Preview {
+    NotRun,
+    Running,
+    Matches(usize),
+    Failed(String),
+}
+
+/// The editable filters, in the order they're listed.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum FilterField {
+    DateAfter,
+    DateBefore,
+    MinDuration,
+    MaxDuration,
+    TitleRegex,
+    MaxItems,
+}
+
+pub const FIELDS: [FilterField; 6] = [
+    FilterField::DateAfter,
+    FilterField::DateBefore,
+    FilterField::MinDuration,
+    FilterField::MaxDuration,
+    FilterField::TitleRegex,
+    FilterField::MaxItems,
+];
+
+const DATE_UNITS: [&str; 4] = ["day", "week", "month", "year"];
+
+impl ChannelFilters {
+    pub fn is_empty(&self) -> bool {
+        *self == ChannelFilters::default()
+    }
+
+    /// yt-dlp options that apply the filters.
+    pub fn args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if let Some(date) = &self.date_after {
+            args.extend(["--dateafter".to_string(), date.clone()]);
+        }
+        if let Some(date) = &self.date_before {
+            args.extend(["--datebefore".to_string(), date.clone()]);
+        }
+        if let Some(filter) = self.match_filter() {
+            args.extend(["--match-filter".to_string(), filter]);
+        }
+        if let Some(max) = self.max_items {
+            args.extend(["--max-downloads".to_string(), max.to_string()]);
+        }
+        args
+    }
+
+    /// Short description for the status line, e.g. "after 20240101, max 50 videos".
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(date) = &self.date_after {
+            parts.push(format!("after {}", date));
+        }
+        if let Some(date) = &self.date_before {
+            parts.push(format!("before {}", date));
+        }
+        if let Some(min) = self.min_duration {
+            parts.push(format!("≥ {}", format_seconds(min)));
+        }
+        if let Some(max) = self.max_duration {
+            parts.push(format!("≤ {}", format_seconds(max)));
+        }
+        if let Some(regex) = &self.title_regex {
+            parts.push(format!("title ~ {}", regex));
+        }
+        if let Some(max) = self.max_items {
+            parts.push(format!("max {} videos", max));
+        }
+        parts.join(", ")
+    }
+
+    fn match_filter(&self) -> Option<String> {
+        let mut conditions = Vec::new();
+        if let Some(min) = self.min_duration {
+            conditions.push(format!("duration >= {}", min));
+        }
+        if let Some(max) = self.max_duration {
+            conditions.push(format!("duration <= {}", max));
+        }
+        if let Some(regex) = &self.title_regex {
+            // Quotes and '&' (which separates conditions) must be escaped inside the value
+            let escaped = regex.replace('\'', "\\'").replace('&', "\\&");
+            conditions.push(format!("title ~= '(?i){}'", escaped));
+        }
+        if conditions.is_empty() {
+            None
+        } else {
+            Some(conditions.join(" & "))
+        }
+    }
+}
+
+impl FilterField {
+    pub fn label(self) -> &'static str {
+        match self {
+            FilterField::DateAfter => "Uploaded after",
+            FilterField::DateBefore => "Uploaded before",
+            FilterField::MinDuration => "Min duration",
+            FilterField::MaxDuration => "Max duration",
+            FilterField::TitleRegex => "Title matches",
+            FilterField::MaxItems => "Max videos",
+        }
+    }
+
+    /// The field's current value as it would be typed, empty if unset.
+    pub fn value(self, filters: &ChannelFilters) -> String {
+        match self {
+            FilterField::DateAfter => filters.date_after.clone().unwrap_or_default(),
+            FilterField::DateBefore => filters.date_before.clone().unwrap_or_default(),
+            FilterField::MinDuration => filters.min_duration.map(format_seconds).unwrap_or_default(),
+            FilterField::MaxDuration => filters.max_duration.map(format_seconds).unwrap_or_default(),
+            FilterField::TitleRegex => filters.title_regex.clone().unwrap_or_default(),
+            FilterField::MaxItems => filters.max_items.map(|n| n.to_string()).unwrap_or_default(),
+        }
+    }
+
+    pub fn hint(self) -> &'static str {
+        match self {
+            FilterField::DateAfter | FilterField::DateBefore => "YYYY-MM-DD or today-2weeks",
+            FilterField::MinDuration | FilterField::MaxDuration => "seconds, m:ss or h:mm:ss",
+            FilterField::TitleRegex => "regular expression, case-insensitive",
+            FilterField::MaxItems => "number of videos",
+        }
+    }
+
+    /// Parses and stores typed input; empty input clears the field.
+    pub fn set(self, filters: &mut ChannelFilters, input: &str) -> Result<(), String> {
+        let input = input.trim();
+        let mut updated = filters.clone();
+        match self {
+            FilterField::DateAfter => updated.date_after = optional(input, parse_date)?,
+            FilterField::DateBefore => updated.date_before = optional(input, parse_date)?,
+            FilterField::MinDuration => updated.min_duration = optional(input, parse_duration)?,
+            FilterField::MaxDuration => updated.max_duration = optional(input, parse_duration)?,
+            FilterField::TitleRegex => updated.title_regex = optional(input, |s| Ok(s.to_string()))?,
+            FilterField::MaxItems => {
+                updated.max_items = optional(input, |s| match s.parse() {
+                    Ok(0) | Err(_) => Err(format!("'{}' is not a positive number", s)),
+                    Ok(n) => Ok(n),
+                })?
+            }
+        }
+
+        if let (Some(min), Some(max)) = (updated.min_duration, updated.max_duration) {
+            if min > max {
+                return Err("Min duration is longer than max duration".to_string());
+            }
+        }
+        if let (Some(after), Some(before)) = (&updated.date_after, &updated.date_before) {
+            // Only absolute dates compare meaningfully as strings
+            if is_absolute_date(after) && is_absolute_date(before) && after > before {
+                return Err("'Uploaded after' is later than 'Uploaded before'".to_string());
+            }
+        }
+        *filters = updated;
+        Ok(())
+    }
+}
+
+fn optional<T>(input: &str, parse: impl Fn(&str) -> Result<T, String>) -> Result<Option<T>, String> {
+    if input.is_empty() {
+        Ok(None)
+    } else {
+        parse(input).map(Some)
+    }
+}
+
+/// Accepts `YYYY-MM-DD`, `YYYYMMDD`, `today`, `now` and `today-N<unit>(s)`, returning yt-dlp's form.
+fn parse_date(input: &str) -> Result<String, String> {
+    let invalid = || format!("'{}' is not a date (use YYYY-MM-DD or today-2weeks)", input);
+    let lower = input.to_lowercase();
+
+    let relative = ["today", "now"].iter().find_map(|base| lower.strip_prefix(base));
+    if let Some(offset) = relative {
+        if offset.is_empty() {
+            return Ok(lower);
+        }
+        let offset = offset.strip_prefix('-').ok_or_else(invalid)?;
+        let digits = offset.chars().take_while(char::is_ascii_digit).count();
+        let unit = offset[digits..].trim_end_matches('s');
+        if digits == 0 || !DATE_UNITS.contains(&unit) {
+            return Err(invalid());
+        }
+        return Ok(lower);
+    }
+
+    let digits: String = input.chars().filter(|c| *c != '-').collect();
+    if !is_absolute_date(&digits) {
+        return Err(invalid());
+    }
+    let month: u32 = digits[4..6].parse().map_err(|_| invalid())?;
+    let day: u32 = digits[6..8].parse().map_err(|_| invalid())?;
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return Err(invalid());
+    }
+    Ok(digits)
+}
+
+fn is_absolute_date(date: &str) -> bool {
+    date.len() == 8 && date.chars().all(|c| c.is_ascii_digit())
+}
+
+/// Accepts plain seconds, `m:ss` or `h:mm:ss`.
+fn parse_duration(input: &str) -> Result<u64, String> {
+    let invalid = || format!("'{}' is not a duration (use seconds, m:ss or h:mm:ss)", input);
+    let parts: Vec<&str> = input.split(':').collect();
+    if parts.len() > 3 {
+        return Err(invalid());
+    }
+    parts.iter().try_fold(0u64, |total, part| {
+        let value: u64 = part.parse().map_err(|_| invalid())?;
+        Ok(total * 60 + value)
+    })
+}
+
+fn format_seconds(seconds: u64) -> String {
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{}:{:02}", minutes, seconds)
+    }
+}
+
+/// Counts the channel's videos that pass the filters without downloading anything.
+///
+/// Listing is flat (fast), so upload dates aren't known yet and the date range is only
+/// applied while downloading.
+pub fn count_matches(ytdlp_path: &Path, url: &str, filters: &ChannelFilters) -> Result<usize, String> {
+    let mut command = Command::new(ytdlp_path);
+    command.args(["--flat-playlist", "--ignore-errors", "--print", "id"]);
+    if let Some(filter) = filters.match_filter() {
+        command.args(["--match-filter", &filter]);
+    }
+    command.arg(url);
+
+    let output = command
+        .output()
+        .map_err(|e| format!("Could not run yt-dlp: {}", e))?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let count = stdout.lines().filter(|line| !line.trim().is_empty()).count();
+    if count == 0 && !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let reason = stderr.lines().rev().find(|l| l.contains("ERROR")).unwrap_or("yt-dlp failed");
+        return Err(reason.trim().to_string());
+    }
+    Ok(filters.max_items.map_or(count, |max| count.min(max as usize)))
+}
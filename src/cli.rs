@@ -0,0 +1,269 @@
+use std::error::Error;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use crate::app::DownloadProgress;
+use crate::batch;
+use crate::config::{Config, Paths};
+use crate::download::{run_download_thread, DownloadedFile};
+use crate::history::{self, HistoryEntry, Outcome};
+use crate::job::{self, Job};
+use crate::subscriptions;
+use crate::url;
+
+const USAGE: &str = "Usage:
+  yt_downloader                     Start the interactive downloader
+  yt_downloader --batch-file FILE [--type TYPE] [--format FORMAT]
+                                    Download the URLs listed in FILE, one per line (default: video, mp4),
+                                    or the jobs of an exported .queue.json file
+  yt_downloader sync [NAME...]      Download new videos of all (or the named) subscriptions
+  yt_downloader subscribe URL [--name NAME] [--type TYPE] [--format FORMAT] [--template TEMPLATE]
+                                    Subscribe to a channel or playlist (default: video, mp4)
+  yt_downloader unsubscribe NAME    Remove a subscription
+  yt_downloader subscriptions       List subscriptions and their last sync";
+
+/// What the program was asked to do on the command line.
+pub enum Command {
+    Tui,
+    Batch {
+        file: PathBuf,
+        download_type: usize,
+        format: usize,
+    },
+    Sync(Vec<String>),
+    Subscribe {
+        url: String,
+        name: Option<String>,
+        download_type: usize,
+        format: usize,
+        template: Option<String>,
+    },
+    Unsubscribe(String),
+    ListSubscriptions,
+    Help,
+}
+
+pub fn parse(args: impl Iterator<Item = String>) -> Result<Command, String> {
+    let args: Vec<String> = args.collect();
+    let Some((command, rest)) = args.split_first() else {
+        return Ok(Command::Tui);
+    };
+
+    match command.as_str() {
+        "--batch-file" => parse_batch(rest),
+        "sync" => Ok(Command::Sync(rest.to_vec())),
+        "subscribe" => parse_subscribe(rest),
+        "unsubscribe" => match rest {
+            [name] => Ok(Command::Unsubscribe(name.clone())),
+            _ => Err(format!("unsubscribe takes one subscription name\n\n{}", USAGE)),
+        },
+        "subscriptions" => Ok(Command::ListSubscriptions),
+        "-h" | "--help" | "help" => Ok(Command::Help),
+        other => Err(format!("Unknown command '{}'\n\n{}", other, USAGE)),
+    }
+}
+
+fn parse_batch(args: &[String]) -> Result<Command, String> {
+    let mut file = None;
+    let mut type_name = None;
+    let mut format_name = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = |option: &str| {
+            args.next()
+                .cloned()
+                .ok_or_else(|| format!("{} needs a value", option))
+        };
+        match arg.as_str() {
+            "--type" => type_name = Some(value(arg)?),
+            "--format" => format_name = Some(value(arg)?),
+            _ if file.is_none() && !arg.starts_with("--") => file = Some(PathBuf::from(arg)),
+            _ => return Err(format!("Unexpected argument '{}'\n\n{}", arg, USAGE)),
+        }
+    }
+
+    let file = file.ok_or_else(|| format!("--batch-file needs a file\n\n{}", USAGE))?;
+    let (download_type, format) =
+        job::parse_settings(type_name.as_deref().unwrap_or("video"), format_name.as_deref())?;
+    Ok(Command::Batch { file, download_type, format })
+}
+
+fn parse_subscribe(args: &[String]) -> Result<Command, String> {
+    let mut url = None;
+    let mut name = None;
+    let mut type_name = None;
+    let mut format_name = None;
+    let mut template = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = |option: &str| {
+            args.next()
+                .cloned()
+                .ok_or_else(|| format!("{} needs a value", option))
+        };
+        match arg.as_str() {
+            "--name" => name = Some(value(arg)?),
+            "--type" => type_name = Some(value(arg)?),
+            "--format" => format_name = Some(value(arg)?),
+            "--template" => template = Some(value(arg)?),
+            _ if url.is_none() && !arg.starts_with("--") => url = Some(arg.clone()),
+            _ => return Err(format!("Unexpected argument '{}'\n\n{}", arg, USAGE)),
+        }
+    }
+
+    let url = url.ok_or_else(|| format!("subscribe needs a URL\n\n{}", USAGE))?;
+    let parsed = url::parse(&url)?;
+    let Some(url) = parsed.playlist().or_else(|| parsed.channel_url()) else {
+        return Err("Only channels and playlists can be subscribed to".to_string());
+    };
+
+    let (download_type, format) =
+        job::parse_settings(type_name.as_deref().unwrap_or("video"), format_name.as_deref())?;
+    Ok(Command::Subscribe { url, name, download_type, format, template })
+}
+
+/// Runs a command that doesn't need the interactive UI.
+pub fn run(command: Command, paths: &Paths, config: &Config) -> Result<(), Box<dyn Error>> {
+    match command {
+        Command::Tui => Ok(()),
+        Command::Help => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        Command::Batch { file, download_type, format } => run_batch(paths, config, &file, download_type, format),
+        Command::Sync(names) => sync(paths, config, &names),
+        Command::Subscribe { url, name, download_type, format, template } => {
+            let name = name.unwrap_or_else(|| subscriptions::default_name(&url));
+            let job = Job { template, ..Job::new(url, download_type, format) };
+            let subscription = subscriptions::add(&paths.subscriptions_file, &name, &job)?;
+            println!(
+                "Subscribed to '{}' ({}). Run `yt_downloader sync` to download it.",
+                subscription.name, job.url
+            );
+            Ok(())
+        }
+        Command::Unsubscribe(name) => {
+            if !subscriptions::remove(&paths.subscriptions_file, &name)? {
+                return Err(format!("No subscription named '{}'", name).into());
+            }
+            println!("Unsubscribed from '{}'", name);
+            Ok(())
+        }
+        Command::ListSubscriptions => {
+            let subscriptions = subscriptions::load(&paths.subscriptions_file);
+            if subscriptions.is_empty() {
+                println!("No subscriptions yet. Add one with `yt_downloader subscribe URL`.");
+            }
+            for subscription in subscriptions {
+                println!("{}  {}  {}", subscription.name, subscription.describe(), subscription.url);
+                println!("    {}", subscriptions::last_sync_text(&subscription));
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Downloads every job of a batch file in turn, then prints how many succeeded.
+fn run_batch(
+    paths: &Paths,
+    config: &Config,
+    file: &std::path::Path,
+    download_type: usize,
+    format: usize,
+) -> Result<(), Box<dyn Error>> {
+    let jobs = batch::load(file, download_type, format).map_err(|errors| {
+        format!("nothing was downloaded, {} needs fixing:\n  {}", file.display(), errors.join("\n  "))
+    })?;
+
+    let mut failed = Vec::new();
+    for (i, job) in jobs.iter().enumerate() {
+        println!(
+            "[{}/{}] {} ({}/{})",
+            i + 1,
+            jobs.len(),
+            job.url,
+            job::type_name(job.download_type),
+            job::format_name(job.download_type, job.format)
+        );
+        let (outcome, files, reason) = download(job, paths, config)?;
+        history::append(&paths.history_file, HistoryEntry::from_result(job, outcome, &files))?;
+        for file in &files {
+            println!("  + {}", file.title);
+        }
+        if outcome == Outcome::Failed {
+            println!("  {}", reason);
+            failed.push(&job.url);
+        }
+    }
+
+    println!("{} of {} downloads succeeded", jobs.len() - failed.len(), jobs.len());
+    if !failed.is_empty() {
+        for url in &failed {
+            println!("  failed: {}", url);
+        }
+        return Err(format!("{} of {} downloads failed", failed.len(), jobs.len()).into());
+    }
+    Ok(())
+}
+
+/// Runs one job to completion, returning its outcome, files and final message.
+fn download(
+    job: &Job,
+    paths: &Paths,
+    config: &Config,
+) -> Result<(Outcome, Vec<DownloadedFile>, String), Box<dyn Error>> {
+    let progress = Arc::new(Mutex::new(DownloadProgress::default()));
+    run_download_thread(job, &paths.ytdlp, &paths.output_dir, config, progress.clone());
+
+    let progress = progress.lock().map_err(|_| "Download thread panicked")?;
+    // The status message ends with key hints meant for the interactive UI
+    let reason = match progress.log.iter().rev().find(|line| line.contains("ERROR")) {
+        Some(error) => error.clone(),
+        None => progress.message.split(" Press '").next().unwrap_or_default().to_string(),
+    };
+    Ok((progress.outcome.unwrap_or(Outcome::Failed), progress.files.clone(), reason))
+}
+
+/// Downloads what's new for each subscription, printing what was fetched.
+fn sync(paths: &Paths, config: &Config, names: &[String]) -> Result<(), Box<dyn Error>> {
+    let all = subscriptions::load(&paths.subscriptions_file);
+    if let Some(unknown) = names.iter().find(|name| !all.iter().any(|s| &s.name == *name)) {
+        return Err(format!("No subscription named '{}'", unknown).into());
+    }
+    let selected: Vec<_> = all
+        .into_iter()
+        .filter(|s| names.is_empty() || names.contains(&s.name))
+        .collect();
+    if selected.is_empty() {
+        println!("No subscriptions yet. Add one with `yt_downloader subscribe URL`.");
+        return Ok(());
+    }
+    std::fs::create_dir_all(&paths.archive_dir)?;
+
+    let mut failed = 0;
+    for subscription in &selected {
+        println!("Syncing {} ({})...", subscription.name, subscription.describe());
+        let job = subscription.job(&paths.archive_dir);
+        let (outcome, files, reason) = download(&job, paths, config)?;
+        subscriptions::record_sync(&paths.subscriptions_file, &paths.archive_dir, &job, outcome, files.len())?;
+        if !files.is_empty() || outcome != Outcome::Success {
+            history::append(&paths.history_file, HistoryEntry::from_result(&job, outcome, &files))?;
+        }
+
+        println!("  {}", subscriptions::sync_summary(outcome, files.len()));
+        for file in &files {
+            println!("  + {}", file.title);
+        }
+        if outcome == Outcome::Failed {
+            println!("  {}", reason);
+            failed += 1;
+        }
+    }
+
+    if failed > 0 {
+        return Err(format!("{} of {} subscriptions failed to sync", failed, selected.len()).into());
+    }
+    Ok(())
+}
@@ -0,0 +1,26 @@
+use std::io;
+use std::process::Command;
+
+/// Clipboard readers to try, in order, for the current platform.
+#[cfg(target_os = "windows")]
+const PROVIDERS: [(&str, &[&str]); 1] = [("powershell", &["-NoProfile", "-Command", "Get-Clipboard"])];
+#[cfg(target_os = "macos")]
+const PROVIDERS: [(&str, &[&str]); 1] = [("pbpaste", &[])];
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+const PROVIDERS: [(&str, &[&str]); 3] = [
+    ("wl-paste", &["--no-newline"]),
+    ("xclip", &["-selection", "clipboard", "-o"]),
+    ("xsel", &["--clipboard", "--output"]),
+];
+
+/// Reads the system clipboard as text using the platform's clipboard tool.
+pub fn read_text() -> io::Result<String> {
+    for (program, args) in PROVIDERS {
+        if let Ok(output) = Command::new(program).args(args).output() {
+            if output.status.success() {
+                return Ok(String::from_utf8_lossy(&output.stdout).to_string());
+            }
+        }
+    }
+    Err(io::Error::new(io::ErrorKind::NotFound, "no clipboard tool available"))
+}
@@ -0,0 +1,117 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::keymap::Action;
+use crate::theme::CustomTheme;
+
+/// Locations of the tools and files the app uses, relative to the project root.
+pub struct Paths {
+    /// The project root; the import screen lists the text files in it.
+    pub base_dir: PathBuf,
+    pub ytdlp: PathBuf,
+    pub output_dir: PathBuf,
+    pub config_file: PathBuf,
+    pub jobs_file: PathBuf,
+    pub history_file: PathBuf,
+    pub crash_log: PathBuf,
+    pub subscriptions_file: PathBuf,
+    /// yt-dlp download archives, one per subscription.
+    pub archive_dir: PathBuf,
+}
+
+impl Paths {
+    pub fn new(base_dir: &Path) -> Self {
+        let setup_dir = base_dir.join("setup");
+        Paths {
+            base_dir: base_dir.to_path_buf(),
+            ytdlp: setup_dir.join("yt-dlp.exe"),
+            output_dir: base_dir.join("output"),
+            config_file: base_dir.join("config.toml"),
+            jobs_file: base_dir.join("jobs.json"),
+            history_file: base_dir.join("history.json"),
+            crash_log: base_dir.join("crash.log"),
+            subscriptions_file: base_dir.join("subscriptions.json"),
+            archive_dir: base_dir.join("archives"),
+        }
+    }
+}
+
+#[derive(Deserialize, Default, Clone)]
+#[serde(default)]
+pub struct Config {
+    pub retry: RetryConfig,
+    /// Name of a built-in theme or one defined under `[themes.<name>]`.
+    pub theme: Option<String>,
+    pub themes: HashMap<String, CustomTheme>,
+    /// Keys per action, replacing that action's default keys.
+    pub keys: HashMap<Action, Vec<String>>,
+    /// Commands run after each download, in order.
+    pub hooks: Vec<Hook>,
+}
+
+/// A shell command run after a download finishes, configured under `[[hooks]]`.
+#[derive(Deserialize, Clone)]
+pub struct Hook {
+    pub command: String,
+    #[serde(default)]
+    pub on: HookTrigger,
+    /// The hook is killed if it runs longer than this.
+    #[serde(default = "default_hook_timeout")]
+    pub timeout_secs: u64,
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum HookTrigger {
+    Success,
+    Failure,
+    #[default]
+    Always,
+}
+
+fn default_hook_timeout() -> u64 {
+    60
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(default)]
+pub struct RetryConfig {
+    pub max_attempts: u32,
+    pub initial_delay_secs: u64,
+    pub max_delay_secs: u64,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        RetryConfig {
+            max_attempts: 3,
+            initial_delay_secs: 2,
+            max_delay_secs: 60,
+        }
+    }
+}
+
+impl RetryConfig {
+    /// Delay before the given retry (1 = first retry), doubling each time.
+    pub fn delay_for(&self, retry: u32) -> Duration {
+        let factor = 2u64.saturating_pow(retry.saturating_sub(1));
+        let secs = self.initial_delay_secs.saturating_mul(factor);
+        Duration::from_secs(secs.min(self.max_delay_secs))
+    }
+}
+
+impl Config {
+    /// Loads the config file, falling back to defaults when it doesn't exist.
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        if !path.exists() {
+            return Ok(Config::default());
+        }
+        let contents = std::fs::read_to_string(path)?;
+        let config = toml::from_str(&contents)
+            .map_err(|e| format!("Invalid config file {}: {}", path.display(), e))?;
+        Ok(config)
+    }
+}
@@ -0,0 +1,61 @@
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+const ELLIPSIS: &str = "…";
+
+/// Number of terminal columns the text occupies.
+pub fn width(text: &str) -> usize {
+    UnicodeWidthStr::width(text)
+}
+
+/// Shortens text to `max_width` columns, replacing the end with an ellipsis.
+///
+/// Cuts only between grapheme clusters, so multibyte characters, combining marks
+/// and emoji sequences are never split.
+pub fn truncate_end(text: &str, max_width: usize) -> String {
+    if width(text) <= max_width {
+        return text.to_string();
+    }
+    if max_width == 0 {
+        return String::new();
+    }
+
+    let mut result = take_width(text.graphemes(true), max_width - 1);
+    result.push_str(ELLIPSIS);
+    result
+}
+
+/// Shortens text to `max_width` columns by replacing its middle with an ellipsis.
+///
+/// Suited to URLs and paths, where both the start and the end carry meaning.
+pub fn truncate_middle(text: &str, max_width: usize) -> String {
+    if width(text) <= max_width {
+        return text.to_string();
+    }
+    if max_width < 3 {
+        return truncate_end(text, max_width);
+    }
+
+    let available = max_width - 1;
+    let head_width = available.div_ceil(2);
+    let tail_width = available - head_width;
+
+    let head = take_width(text.graphemes(true), head_width);
+    let tail_reversed: Vec<&str> = collect_width(text.graphemes(true).rev(), tail_width);
+    let tail: String = tail_reversed.into_iter().rev().collect();
+    format!("{}{}{}", head, ELLIPSIS, tail)
+}
+
+fn take_width<'a>(graphemes: impl Iterator<Item = &'a str>, max_width: usize) -> String {
+    collect_width(graphemes, max_width).concat()
+}
+
+fn collect_width<'a>(graphemes: impl Iterator<Item = &'a str>, max_width: usize) -> Vec<&'a str> {
+    let mut used = 0;
+    graphemes
+        .take_while(|g| {
+            used += width(g);
+            used <= max_width
+        })
+        .collect()
+}
@@ -1,138 +1,537 @@
-use std::process::Command;
+use std::fs::OpenOptions;
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, ExitStatus, Stdio};
 use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
 use crate::app::DownloadProgress;
+use crate::config::{Config, Hook, HookTrigger};
+use crate::history::Outcome;
+use crate::job::{self, Job};
+
+/// Prefix marking the lines yt-dlp prints for every finished file.
+const FILE_MARKER: &str = "YTDL_FILE";
+/// Printed once a file has been moved to its final location.
+const FILE_TEMPLATE: &str = "after_move:YTDL_FILE\t%(duration)s\t%(filepath)s\t%(title)s";
+
+const AUDIO_FORMATS: [&str; 5] = ["flac", "mp3", "wav", "aac", "m4a"];
+const VIDEO_FORMATS: [&str; 3] = ["mp4", "mkv", "webm"];
+
+/// How often a running download checks whether it was cancelled.
+const CANCEL_POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// Log file for downloads handed off to a background yt-dlp process.
+const DETACHED_LOG: &str = "yt-dlp-detached.log";
+/// yt-dlp's exit code when it stopped because `--max-downloads` was reached.
+const MAX_DOWNLOADS_REACHED: i32 = 101;
+
+/// Messages yt-dlp prints for failures that retrying won't fix.
+const PERMANENT_ERRORS: [&str; 10] = [
+    "Video unavailable",
+    "Private video",
+    "This video has been removed",
+    "This video is not available",
+    "members-only",
+    "Sign in to confirm your age",
+    "copyright",
+    "Unsupported URL",
+    "is not a valid URL",
+    "HTTP Error 404",
+];
+
+/// A file produced by a download, as reported by yt-dlp.
+#[derive(Clone)]
+pub struct DownloadedFile {
+    pub title: String,
+    pub path: PathBuf,
+    pub size: Option<u64>,
+    pub duration: Option<f64>,
+}
+
+enum DownloadResult {
+    Success,
+    NoSubtitles,
+    Retryable,
+    Permanent(String),
+    Cancelled,
+}
+
+/// What a yt-dlp run produced; `status` is `None` if it was cancelled.
+struct CommandOutput {
+    status: Option<ExitStatus>,
+    stdout: Vec<u8>,
+    stderr: Vec<u8>,
+}
 
 pub fn run_download_thread(
-    download_type: usize,
-    format: usize,
-    url: &str,
-    ytdlp_path: &std::path::PathBuf,
-    output_dir: &std::path::PathBuf,
+    job: &Job,
+    ytdlp_path: &Path,
+    output_dir: &Path,
+    config: &Config,
     progress: Arc<Mutex<DownloadProgress>>,
 ) {
+    let retry = &config.retry;
+    let max_attempts = retry.max_attempts.max(1);
+    let mut attempt = 1;
+
+    let mut files = Vec::new();
+    progress.lock().unwrap().push_log(format!("=== {} ===", job.url));
+    let output_dir = job.output_dir(output_dir);
+    if let Err(e) = std::fs::create_dir_all(&output_dir) {
+        let reason = format!("could not create {}: {}", output_dir.display(), e);
+        update_progress_with_result(DownloadResult::Permanent(reason), job.download_type, attempt, files, progress);
+        return;
+    }
+    let result = loop {
+        {
+            let mut prog = progress.lock().unwrap();
+            prog.message = progress_message(job);
+            prog.notify();
+        }
+        let mut command = build_command(job, ytdlp_path, &output_dir);
+        command.stdout(Stdio::piped());
+        command.stderr(Stdio::piped());
+
+        let output = run_command(&mut command, &progress);
+        if let Ok(output) = &output {
+            files.extend(parse_downloaded_files(&output.stdout, &output_dir));
+        }
+        let result = classify_output(output);
+
+        if !matches!(result, DownloadResult::Retryable) || attempt >= max_attempts {
+            break result;
+        }
+
+        let delay = retry.delay_for(attempt);
+        {
+            let mut prog = progress.lock().unwrap();
+            prog.message = format!(
+                "Attempt {}/{} failed, retrying in {}s...",
+                attempt, max_attempts, delay.as_secs()
+            );
+            prog.notify();
+        }
+        if !sleep_unless_cancelled(delay, &progress) {
+            break DownloadResult::Cancelled;
+        }
+        attempt += 1;
+    };
+
+    // A cancelled job was stopped on purpose, so hooks don't run for it
+    let outcome = outcome_of(&result);
+    if outcome != Outcome::Cancelled {
+        let hooks: Vec<&Hook> = config.hooks.iter().filter(|hook| hook.runs_for(outcome)).collect();
+        if !hooks.is_empty() {
+            let context = HookContext { job, outcome, output_dir: &output_dir, files: &files, result: &result };
+            run_hooks(&hooks, &context, &progress);
+        }
+    }
+
+    update_progress_with_result(result, job.download_type, attempt, files, progress);
+}
+
+/// What hooks are told about the finished job, through environment variables.
+struct HookContext<'a> {
+    job: &'a Job,
+    outcome: Outcome,
+    output_dir: &'a Path,
+    files: &'a [DownloadedFile],
+    result: &'a DownloadResult,
+}
+
+impl Hook {
+    fn runs_for(&self, outcome: Outcome) -> bool {
+        match self.on {
+            HookTrigger::Always => true,
+            HookTrigger::Success => matches!(outcome, Outcome::Success | Outcome::NoSubtitles),
+            HookTrigger::Failure => outcome == Outcome::Failed,
+        }
+    }
+}
+
+/// Runs each hook in a shell, copying its output into the job log.
+fn run_hooks(hooks: &[&Hook], context: &HookContext, progress: &Arc<Mutex<DownloadProgress>>) {
+    for hook in hooks {
+        {
+            let mut prog = progress.lock().unwrap();
+            prog.message = "Running post-download hooks...".to_string();
+            prog.push_log(format!("--- hook: {} ---", hook.command));
+        }
+
+        let mut command = shell_command(&hook.command);
+        command.current_dir(context.output_dir);
+        command.envs(hook_env(context));
+        command.stdin(Stdio::null());
+        command.stdout(Stdio::piped());
+        command.stderr(Stdio::piped());
+
+        let timeout = Duration::from_secs(hook.timeout_secs);
+        let result = command.spawn().and_then(|mut child| {
+            let stdout_reader = read_in_background(child.stdout.take(), progress.clone());
+            let stderr_reader = read_in_background(child.stderr.take(), progress.clone());
+            let status = wait_with_timeout(&mut child, timeout);
+            let _ = stdout_reader.join();
+            let _ = stderr_reader.join();
+            status
+        });
+
+        let summary = match result {
+            Ok(Some(status)) if status.success() => None,
+            Ok(Some(status)) => Some(format!("hook failed ({})", status)),
+            Ok(None) => Some(format!("hook killed after {}s timeout", hook.timeout_secs)),
+            Err(e) => Some(format!("hook could not be started: {}", e)),
+        };
+        if let Some(summary) = summary {
+            progress.lock().unwrap().push_log(format!("WARNING: {}", summary));
+        }
+    }
+}
+
+fn shell_command(script: &str) -> Command {
+    #[cfg(windows)]
+    {
+        let mut command = Command::new("cmd");
+        command.args(["/C", script]);
+        command
+    }
+    #[cfg(unix)]
+    {
+        // Its own process group, so a timeout also stops whatever the script started
+        use std::os::unix::process::CommandExt;
+        let mut command = Command::new("sh");
+        command.args(["-c", script]);
+        command.process_group(0);
+        command
+    }
+}
+
+fn hook_env(context: &HookContext) -> Vec<(&'static str, String)> {
+    let job = context.job;
+    let status = match context.outcome {
+        Outcome::Success => "success",
+        Outcome::NoSubtitles => "no_subtitles",
+        Outcome::Failed => "failed",
+        Outcome::Cancelled => "cancelled",
+    };
+    let paths: Vec<String> = context.files.iter().map(|f| f.path.display().to_string()).collect();
+    let error = match context.result {
+        DownloadResult::Permanent(reason) => reason.clone(),
+        DownloadResult::Retryable => "download failed after retrying".to_string(),
+        _ => String::new(),
+    };
+
+    vec![
+        ("YTD_STATUS", status.to_string()),
+        ("YTD_URL", job.url.clone()),
+        ("YTD_TYPE", job::type_name(job.download_type).to_lowercase()),
+        ("YTD_FORMAT", job::format_name(job.download_type, job.format).to_lowercase()),
+        ("YTD_TITLE", context.files.first().map(|f| f.title.clone()).unwrap_or_default()),
+        ("YTD_OUTPUT_PATH", paths.first().cloned().unwrap_or_default()),
+        // Playlists and channels produce several files, one per line
+        ("YTD_OUTPUT_PATHS", paths.join("\n")),
+        ("YTD_OUTPUT_DIR", context.output_dir.display().to_string()),
+        ("YTD_ERROR", error),
+    ]
+}
+
+/// Waits for the child, killing it once `timeout` has passed; `None` means it was killed.
+fn wait_with_timeout(child: &mut Child, timeout: Duration) -> io::Result<Option<ExitStatus>> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(Some(status));
+        }
+        if Instant::now() >= deadline {
+            #[cfg(unix)]
+            {
+                let _ = Command::new("kill").args(["-KILL", &format!("-{}", child.id())]).status();
+            }
+            let _ = child.kill();
+            child.wait()?;
+            return Ok(None);
+        }
+        thread::sleep(CANCEL_POLL_INTERVAL);
+    }
+}
+
+/// Restarts a job as a background yt-dlp process that outlives the app.
+///
+/// Partial files are picked up again through `--continue`, and output goes to a log
+/// file in the output folder instead of the terminal.
+pub fn spawn_detached(job: &Job, ytdlp_path: &Path, output_dir: &Path) -> io::Result<()> {
+    let log = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(output_dir.join(DETACHED_LOG))?;
+
+    let job_dir = job.output_dir(output_dir);
+    std::fs::create_dir_all(&job_dir)?;
+    let mut command = build_command(job, ytdlp_path, &job_dir);
+    command.stdin(Stdio::null());
+    command.stdout(log.try_clone()?);
+    command.stderr(log);
+
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        const DETACHED_PROCESS: u32 = 0x0000_0008;
+        command.creation_flags(DETACHED_PROCESS);
+    }
+    #[cfg(unix)]
+    {
+        // A separate process group keeps terminal signals like Ctrl+C away from it
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+
+    command.spawn()?;
+    Ok(())
+}
+
+/// Runs yt-dlp to completion, killing and reaping it if the download gets cancelled.
+fn run_command(
+    command: &mut Command,
+    progress: &Arc<Mutex<DownloadProgress>>,
+) -> io::Result<CommandOutput> {
+    let mut child = command.spawn()?;
+    let stdout_reader = read_in_background(child.stdout.take(), progress.clone());
+    let stderr_reader = read_in_background(child.stderr.take(), progress.clone());
+
+    let status = wait_unless_cancelled(&mut child, progress)?;
+
+    Ok(CommandOutput {
+        status,
+        stdout: stdout_reader.join().unwrap_or_default(),
+        stderr: stderr_reader.join().unwrap_or_default(),
+    })
+}
+
+fn wait_unless_cancelled(
+    child: &mut Child,
+    progress: &Arc<Mutex<DownloadProgress>>,
+) -> io::Result<Option<ExitStatus>> {
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(Some(status));
+        }
+        if progress.lock().map(|p| p.cancel_requested).unwrap_or(true) {
+            // kill() fails if it exited in the meantime; wait() reaps it either way
+            let _ = child.kill();
+            child.wait()?;
+            return Ok(None);
+        }
+        thread::sleep(CANCEL_POLL_INTERVAL);
+    }
+}
+
+/// Collects a pipe's output, copying each line into the job log as it arrives.
+fn read_in_background<R: Read + Send + 'static>(
+    pipe: Option<R>,
+    progress: Arc<Mutex<DownloadProgress>>,
+) -> thread::JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut buffer = Vec::new();
+        let mut reader = match pipe {
+            Some(pipe) => BufReader::new(pipe),
+            None => return buffer,
+        };
+        let mut line = Vec::new();
+        while let Ok(read) = reader.read_until(b'\n', &mut line) {
+            if read == 0 {
+                break;
+            }
+            if let Ok(mut prog) = progress.lock() {
+                prog.push_log(String::from_utf8_lossy(&line).trim_end().to_string());
+            }
+            buffer.append(&mut line);
+        }
+        buffer
+    })
+}
+
+/// Sleeps for `delay`, returning false early if the download gets cancelled.
+fn sleep_unless_cancelled(delay: Duration, progress: &Arc<Mutex<DownloadProgress>>) -> bool {
+    let deadline = Instant::now() + delay;
+    while Instant::now() < deadline {
+        if progress.lock().map(|p| p.cancel_requested).unwrap_or(true) {
+            return false;
+        }
+        thread::sleep(CANCEL_POLL_INTERVAL);
+    }
+    true
+}
+
+fn build_command(job: &Job, ytdlp_path: &Path, output_dir: &Path) -> Command {
     let mut command = Command::new(ytdlp_path);
     command.current_dir(output_dir);
-    
-    use std::process::Stdio;
-    command.stdout(Stdio::piped());
-    command.stderr(Stdio::piped());
+    command.args(["--print", FILE_TEMPLATE]);
+    // --print implies --quiet; keep progress output, one line per update, for the log
+    command.args(["--progress", "--newline"]);
+    if let Some(filters) = &job.filters {
+        command.args(filters.args());
+    }
+    if let Some(archive) = &job.archive {
+        command.arg("--download-archive").arg(archive);
+    }
 
-    match download_type {
+    match job.download_type {
         1 => { // Audio
-            configure_audio_download(&mut command, format, url, &progress);
+            configure_audio_download(&mut command, job.format, job.template(), &job.url);
         }
         0 => { // Video
-            configure_video_download(&mut command, format, url, &progress);
+            configure_video_download(&mut command, job.format, job.template(), &job.url);
         }
         2 => { // Subtitles
-            configure_subtitle_download(&mut command, format, url, &progress);
+            configure_subtitle_download(&mut command, job.format, job.template(), &job.url);
         }
         _ => {}
     }
 
-    let output = command.output();
-    update_progress_with_result(output, download_type, progress);
+    command
 }
 
-fn configure_audio_download(
-    command: &mut Command,
-    format: usize,
-    url: &str,
-    progress: &Arc<Mutex<DownloadProgress>>,
-) {
-    let audio_formats = ["flac", "mp3", "wav", "aac", "m4a"];
-    command.args(&[
+fn progress_message(job: &Job) -> String {
+    match job.download_type {
+        1 => format!("Downloading audio in {} format...", AUDIO_FORMATS[job.format]),
+        0 => format!("Downloading video in {} format...", VIDEO_FORMATS[job.format]),
+        _ => "Downloading subtitles...".to_string(),
+    }
+}
+
+fn configure_audio_download(command: &mut Command, format: usize, template: &str, url: &str) {
+    command.args([
         "-f", "bestaudio/best",
         "-ciw",
-        "-o", "%(title)s.%(ext)s",
+        "-o", template,
         "--extract-audio",
-        "--audio-format", audio_formats[format],
+        "--audio-format", AUDIO_FORMATS[format],
         url,
     ]);
-    
-    let mut prog = progress.lock().unwrap();
-    prog.message = format!("Downloading audio in {} format...", audio_formats[format]);
 }
 
-fn configure_video_download(
-    command: &mut Command,
-    format: usize,
-    url: &str,
-    progress: &Arc<Mutex<DownloadProgress>>,
-) {
-    let video_formats = ["mp4", "mkv", "webm"];
-    let format_str = match video_formats[format] {
+fn configure_video_download(command: &mut Command, format: usize, template: &str, url: &str) {
+    let format_str = match VIDEO_FORMATS[format] {
         "mp4" => "bestvideo[ext=mp4]+bestaudio[ext=m4a]/best[ext=mp4]/best",
         "mkv" => "bestvideo[ext=webm]+bestaudio/best[ext=mkv]/best",
         "webm" => "bestvideo[ext=webm]+bestaudio/best[ext=webm]/best",
         _ => "bestvideo+bestaudio/best",
     };
 
-    command.args(&[
+    command.args([
         "-f", format_str,
         "-ciw",
-        "-o", "%(title)s.%(ext)s",
+        "-o", template,
         url,
     ]);
-    
-    let mut prog = progress.lock().unwrap();
-    prog.message = format!("Downloading video in {} format...", video_formats[format]);
 }
 
-fn configure_subtitle_download(
-    command: &mut Command,
-    format: usize,
-    url: &str,
-    progress: &Arc<Mutex<DownloadProgress>>,
-) {
-    command.args(&[
+fn configure_subtitle_download(command: &mut Command, format: usize, template: &str, url: &str) {
+    command.args([
         "--skip-download",
         "--write-subs",
         "--write-auto-subs",
         "--sub-format", "srt",
-        "-o", "%(title)s.%(ext)s",
+        "-o", template,
         url,
     ]);
 
     if format == 0 {
-        command.args(&["--sub-langs", "en"]);
+        command.args(["--sub-langs", "en"]);
     } else {
-        command.args(&["--sub-langs", "all"]);
+        command.args(["--sub-langs", "all"]);
+    }
+}
+
+fn parse_downloaded_files(stdout: &[u8], output_dir: &Path) -> Vec<DownloadedFile> {
+    String::from_utf8_lossy(stdout)
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.trim_end().splitn(4, '\t');
+            if fields.next()? != FILE_MARKER {
+                return None;
+            }
+            let duration = fields.next()?.parse().ok();
+            let path = output_dir.join(fields.next()?);
+            let title = fields.next()?.to_string();
+            let size = std::fs::metadata(&path).map(|m| m.len()).ok();
+            Some(DownloadedFile { title, path, size, duration })
+        })
+        .collect()
+}
+
+fn classify_output(output: io::Result<CommandOutput>) -> DownloadResult {
+    match output {
+        Ok(output) => {
+            let status = match output.status {
+                Some(status) => status,
+                None => return DownloadResult::Cancelled,
+            };
+            if status.success() || status.code() == Some(MAX_DOWNLOADS_REACHED) {
+                return DownloadResult::Success;
+            }
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            if stderr.contains("no subtitles") || stderr.contains("No subtitles") {
+                return DownloadResult::NoSubtitles;
+            }
+            match PERMANENT_ERRORS.iter().find(|pattern| stderr.contains(*pattern)) {
+                Some(pattern) => DownloadResult::Permanent(pattern.to_string()),
+                None => DownloadResult::Retryable,
+            }
+        }
+        // yt-dlp couldn't be started at all, retrying won't help
+        Err(e) => DownloadResult::Permanent(e.to_string()),
+    }
+}
+
+fn outcome_of(result: &DownloadResult) -> Outcome {
+    match result {
+        DownloadResult::Success => Outcome::Success,
+        DownloadResult::NoSubtitles => Outcome::NoSubtitles,
+        DownloadResult::Retryable | DownloadResult::Permanent(_) => Outcome::Failed,
+        DownloadResult::Cancelled => Outcome::Cancelled,
     }
-    
-    let mut prog = progress.lock().unwrap();
-    prog.message = "Downloading subtitles...".to_string();
 }
 
 fn update_progress_with_result(
-    output: Result<std::process::Output, std::io::Error>,
+    result: DownloadResult,
     download_type: usize,
+    attempts: u32,
+    files: Vec<DownloadedFile>,
     progress: Arc<Mutex<DownloadProgress>>,
 ) {
     let mut prog = progress.lock().unwrap();
     prog.active = false;
-    
-    match output {
-        Ok(output) => {
-            if output.status.success() {
-                if download_type == 2 {
-                    // For subtitle downloads, always show success if the command succeeded
-                    let success_msg = "Subtitles downloaded successfully!";
-                    prog.message = format!("{} Press 'r' to restart or 'q' to quit", success_msg);
-                } else {
-                    prog.message = "Download complete! Press 'r' to restart or 'q' to quit".to_string();
-                }
+    prog.files = files;
+    prog.outcome = Some(outcome_of(&result));
+
+    match result {
+        DownloadResult::Success => {
+            if download_type == 2 {
+                // For subtitle downloads, always show success if the command succeeded
+                let success_msg = "Subtitles downloaded successfully!";
+                prog.message = format!("{} Press 'r' to restart or 'q' to quit", success_msg);
             } else {
-                let stderr = String::from_utf8_lossy(&output.stderr);
-                if stderr.contains("no subtitles") || stderr.contains("No subtitles") {
-                    prog.message = "No subtitles available! Press 'r' to restart or 'q' to quit".to_string();
-                } else {
-                    prog.message = "Download failed! Press 'r' to restart or 'q' to quit".to_string();
-                }
+                prog.message = "Download complete! Press 'r' to restart or 'q' to quit".to_string();
             }
         }
-        Err(_) => {
-            prog.message = "Download failed! Press 'r' to restart or 'q' to quit".to_string();
+        DownloadResult::NoSubtitles => {
+            prog.message = "No subtitles available! Press 'r' to restart or 'q' to quit".to_string();
+        }
+        DownloadResult::Retryable => {
+            prog.message = format!(
+                "Download failed after {} attempts! Press 't' to retry, 'r' to restart or 'q' to quit",
+                attempts
+            );
+        }
+        DownloadResult::Permanent(reason) => {
+            prog.message = format!(
+                "Download failed: {}. Press 't' to retry, 'r' to restart or 'q' to quit",
+                reason
+            );
+        }
+        DownloadResult::Cancelled => {
+            prog.message = "Download cancelled. Press 'r' to restart or 'q' to quit".to_string();
         }
     }
-}
\ No newline at end of file
+    prog.notify();
+}
@@ -0,0 +1,36 @@
+use crossterm::event::{self, Event};
+use std::sync::mpsc::Sender;
+use std::thread;
+use std::time::Duration;
+
+/// How often the spinner advances while a download is running.
+pub const TICK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Everything the main loop reacts to, merged into one channel.
+pub enum AppEvent {
+    /// Keys, mouse, paste and resize events from the terminal.
+    Terminal(Event),
+    /// The download thread changed the shared progress.
+    Progress,
+    Tick,
+}
+
+/// Forwards terminal events until the receiving side is gone.
+pub fn spawn_terminal_reader(events: Sender<AppEvent>) {
+    thread::spawn(move || {
+        while let Ok(event) = event::read() {
+            if events.send(AppEvent::Terminal(event)).is_err() {
+                break;
+            }
+        }
+    });
+}
+
+pub fn spawn_ticker(events: Sender<AppEvent>) {
+    thread::spawn(move || loop {
+        thread::sleep(TICK_INTERVAL);
+        if events.send(AppEvent::Tick).is_err() {
+            break;
+        }
+    });
+}
@@ -1,41 +1,616 @@
-use crossterm::event::KeyCode;
+use crossterm::event::{KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::Rect;
 use std::error::Error;
+use std::sync::{Arc, Mutex};
 use std::thread;
 use crate::app::{AppState, AppStep};
-use crate::download::run_download_thread;
+use crate::batch;
+use crate::channel::{self, Preview};
+use crate::clipboard;
+use crate::config::{Config, Paths};
+use crate::download::{self, run_download_thread};
+use crate::events::AppEvent;
+use crate::history::{self, HistoryView, Outcome};
+use crate::job::{self, Job};
+use crate::keymap::Action;
+use crate::opener;
+use crate::queue;
+use crate::resume;
+use crate::subscriptions;
+use crate::url::{self, UrlKind};
 
 pub fn handle_key_event(
     app: &mut AppState,
-    key_code: KeyCode,
-    ytdlp_path: &std::path::PathBuf,
-    output_dir: &std::path::PathBuf,
+    key: KeyEvent,
+    paths: &Paths,
+    config: &Config,
 ) -> Result<bool, Box<dyn Error>> {
-    match key_code {
-        KeyCode::Esc => return Ok(true), // Signal to quit
-        KeyCode::Char('q') if !app.input_mode => return Ok(true),
-        KeyCode::Char(c) if app.input_mode && app.step == AppStep::EnterUrl => {
-            app.url.push(c);
+    if app.confirm_quit {
+        return handle_quit_confirmation(app, key.code, paths);
+    }
+
+    if app.show_help {
+        // Any key closes the overlay
+        app.show_help = false;
+        return Ok(false);
+    }
+
+    if app.step == AppStep::History {
+        return handle_history_key(app, key, paths);
+    }
+
+    if app.step == AppStep::Subscriptions {
+        return handle_subscriptions_key(app, key, paths, config);
+    }
+
+    if app.step == AppStep::BatchImport {
+        return handle_batch_key(app, key, paths, config);
+    }
+
+    if app.input_mode && app.step == AppStep::ChannelFilters {
+        match app.keymap.action(&key) {
+            Some(Action::Select) => save_filter_input(app),
+            Some(Action::Cancel) => {
+                go_back(app);
+            }
+            Some(Action::Paste) => paste_from_clipboard(app),
+            _ => {
+                if app.filter_input.handle_key(key) {
+                    app.filter_error = None;
+                }
+            }
         }
-        KeyCode::Backspace if app.input_mode && app.step == AppStep::EnterUrl => {
-            app.url.pop();
+        return Ok(false);
+    }
+
+    let backspace_on_empty = key.code == KeyCode::Backspace && app.url_input.is_empty();
+    if app.input_mode
+        && app.step == AppStep::EnterUrl
+        && !backspace_on_empty
+        && app.keymap.action(&key) != Some(Action::Paste)
+        && app.url_input.handle_key(key)
+    {
+        app.url_error = None;
+        return Ok(false);
+    }
+
+    match app.keymap.action(&key) {
+        Some(Action::Cancel) => app.confirm_quit = !go_back(app),
+        Some(Action::Back) if !app.input_mode || backspace_on_empty => {
+            go_back(app);
         }
-        KeyCode::Enter => {
-            handle_enter(app, ytdlp_path, output_dir)?;
+        Some(Action::Quit) if !app.input_mode => app.confirm_quit = true,
+        Some(Action::Help) if !app.input_mode => app.show_help = true,
+        Some(Action::Paste) if app.input_mode => paste_from_clipboard(app),
+        Some(Action::Select) => {
+            handle_enter(app, paths, config)?;
         }
-        KeyCode::Up if !app.input_mode => {
+        Some(Action::Up) if !app.input_mode => {
             move_selection_up(app);
         }
-        KeyCode::Down if !app.input_mode => {
+        Some(Action::Down) if !app.input_mode => {
             move_selection_down(app);
         }
-        KeyCode::Char('r') if app.step == AppStep::Complete => {
+        Some(Action::Restart) if app.step == AppStep::Complete => {
             app.reset();
         }
+        Some(Action::Retry) if app.step == AppStep::Complete && download_failed(app) => {
+            retry_download(app, paths, config)?;
+        }
+        Some(Action::History) if matches!(app.step, AppStep::SelectType | AppStep::Complete) => {
+            open_history(app, paths);
+        }
+        Some(Action::Subscriptions) if matches!(app.step, AppStep::SelectType | AppStep::Complete) => {
+            open_subscriptions(app, paths);
+        }
+        Some(Action::Import) if matches!(app.step, AppStep::SelectType | AppStep::Complete) => {
+            open_batch_import(app, paths);
+        }
+        Some(Action::Export) if matches!(app.step, AppStep::Downloading | AppStep::Complete) => {
+            export_queue(app, paths)?;
+        }
         _ => {}
     }
     Ok(false) // Don't quit
 }
 
+/// Handles clicks on lists and buttons and the scroll wheel over lists and the log.
+pub fn handle_mouse_event(
+    app: &mut AppState,
+    mouse: MouseEvent,
+    paths: &Paths,
+    config: &Config,
+) -> Result<(), Box<dyn Error>> {
+    if app.confirm_quit {
+        return Ok(());
+    }
+    let (column, row) = (mouse.column, mouse.row);
+    let in_log = app.click_areas.log.is_some_and(|area| contains(area, column, row));
+    let in_list = app.click_areas.list.is_some_and(|(area, _)| contains(area, column, row));
+
+    match mouse.kind {
+        MouseEventKind::Down(MouseButton::Left) if app.show_help => app.show_help = false,
+        MouseEventKind::Down(MouseButton::Left) => {
+            let button = app
+                .click_areas
+                .buttons
+                .iter()
+                .find(|(area, _)| contains(*area, column, row))
+                .map(|(_, action)| *action);
+            if let Some(action) = button {
+                handle_button(app, action, paths, config)?;
+            } else if let Some(index) = clicked_list_row(app, column, row) {
+                // A click selects; clicking the selected row activates it like Enter
+                if app.list_state.selected() != Some(index) {
+                    app.list_state.select(Some(index));
+                } else if app.step == AppStep::History {
+                    if let Some(entry) = app.history.visible().get(index).copied() {
+                        redownload(app, entry);
+                    }
+                } else {
+                    handle_enter(app, paths, config)?;
+                }
+            }
+        }
+        MouseEventKind::ScrollUp if in_log => {
+            let log_len = app.download_progress.lock().map(|p| p.log.len()).unwrap_or(0);
+            app.log_scroll = (app.log_scroll + LOG_SCROLL_LINES).min(log_len.saturating_sub(1));
+        }
+        MouseEventKind::ScrollDown if in_log => {
+            app.log_scroll = app.log_scroll.saturating_sub(LOG_SCROLL_LINES);
+        }
+        MouseEventKind::ScrollUp if in_list && !app.show_help => move_selection_up(app),
+        MouseEventKind::ScrollDown if in_list && !app.show_help => move_selection_down(app),
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Lines the log moves per scroll wheel step.
+const LOG_SCROLL_LINES: usize = 3;
+
+fn handle_button(
+    app: &mut AppState,
+    action: Action,
+    paths: &Paths,
+    config: &Config,
+) -> Result<(), Box<dyn Error>> {
+    match action {
+        Action::Retry if download_failed(app) => retry_download(app, paths, config)?,
+        Action::Restart => app.reset(),
+        Action::History => open_history(app, paths),
+        Action::Quit => app.confirm_quit = true,
+        _ => {}
+    }
+    Ok(())
+}
+
+/// The index of the list row under the mouse, if it's on one.
+fn clicked_list_row(app: &AppState, column: u16, row: u16) -> Option<usize> {
+    let (area, offset) = app.click_areas.list?;
+    // The selection also decides which channel filter is being edited
+    if app.input_mode || !contains(area, column, row) {
+        return None;
+    }
+    let index = offset + (row - area.y) as usize;
+    (index < app.get_current_options_len()).then_some(index)
+}
+
+fn contains(area: Rect, column: u16, row: u16) -> bool {
+    column >= area.x && column < area.x + area.width && row >= area.y && row < area.y + area.height
+}
+
+/// Handles pasted text; multiple lines are queued as separate URLs.
+pub fn handle_paste(app: &mut AppState, text: &str) {
+    let mut lines = text.lines().map(str::trim).filter(|line| !line.is_empty());
+    let first = match lines.next() {
+        Some(first) => first,
+        None => return,
+    };
+
+    if app.step == AppStep::History && app.history.searching {
+        app.history.search.push_str(first);
+        app.list_state.select(Some(0));
+        return;
+    }
+    if app.input_mode && app.step == AppStep::ChannelFilters {
+        app.filter_input.insert_str(first);
+        app.filter_error = None;
+        return;
+    }
+    if app.step == AppStep::BatchImport {
+        app.batch_input.insert_str(first);
+        app.batch_errors.clear();
+        return;
+    }
+    if !(app.input_mode && app.step == AppStep::EnterUrl) {
+        return;
+    }
+
+    app.url_error = None;
+    let rest: Vec<String> = lines.map(String::from).collect();
+    if rest.is_empty() {
+        app.url_input.insert_str(first);
+        return;
+    }
+
+    if app.url_input.is_empty() {
+        app.url_input.set_value(first);
+    } else {
+        app.queued_urls.push(first.to_string());
+    }
+    app.queued_urls.extend(rest);
+    app.status = format!(
+        "{} more URL(s) queued with the same settings. Press Enter to continue",
+        app.queued_urls.len()
+    );
+}
+
+fn paste_from_clipboard(app: &mut AppState) {
+    match clipboard::read_text() {
+        Ok(text) => handle_paste(app, &text),
+        Err(e) => app.status = format!("Could not read clipboard: {}", e),
+    }
+}
+
+/// Starts the next queued job, returning false when the queue is empty.
+pub fn start_next_job(
+    app: &mut AppState,
+    paths: &Paths,
+    config: &Config,
+) -> Result<bool, Box<dyn Error>> {
+    let job = match app.queue.pop_front() {
+        Some(job) => job,
+        None => return Ok(false),
+    };
+    app.load_job(job);
+    start_download(app, paths, config)?;
+    Ok(true)
+}
+
+fn handle_quit_confirmation(
+    app: &mut AppState,
+    key_code: KeyCode,
+    paths: &Paths,
+) -> Result<bool, Box<dyn Error>> {
+    if !app.is_downloading() {
+        match key_code {
+            KeyCode::Char('y') | KeyCode::Enter => return Ok(true),
+            KeyCode::Char('n') | KeyCode::Esc => app.confirm_quit = false,
+            _ => {}
+        }
+        return Ok(false);
+    }
+
+    match key_code {
+        KeyCode::Char('w') => {
+            app.confirm_quit = false;
+            app.quit_when_done = true;
+            app.status = "Will quit once all downloads finish. Press 'q' to change".to_string();
+        }
+        KeyCode::Char('c') => {
+            app.stop_download();
+            if let Some(job) = app.current_job() {
+                resume::mark_finished(&paths.jobs_file, &job)?;
+            }
+            return Ok(true);
+        }
+        KeyCode::Char('d') => {
+            detach_downloads(app, paths)?;
+            return Ok(true);
+        }
+        KeyCode::Char('n') | KeyCode::Esc => {
+            app.confirm_quit = false;
+            app.quit_when_done = false;
+        }
+        _ => {}
+    }
+    Ok(false)
+}
+
+/// Hands the running job to a background yt-dlp and leaves queued jobs for the next launch.
+fn detach_downloads(app: &mut AppState, paths: &Paths) -> Result<(), Box<dyn Error>> {
+    app.stop_download();
+    if let Some(job) = app.current_job() {
+        download::spawn_detached(&job, &paths.ytdlp, &paths.output_dir)?;
+        resume::mark_finished(&paths.jobs_file, &job)?;
+    }
+    for job in app.queue.drain(..) {
+        resume::mark_started(&paths.jobs_file, &job)?;
+    }
+    Ok(())
+}
+
+/// Returns to the previous wizard step, keeping earlier choices. Returns false on the first step.
+fn go_back(app: &mut AppState) -> bool {
+    match app.step {
+        AppStep::EnterUrl => {
+            app.input_mode = false;
+            app.url_error = None;
+            app.status = "Select download type using arrow keys and Enter".to_string();
+            app.step = AppStep::SelectType;
+            app.list_state.select(Some(app.download_type.unwrap_or(0)));
+        }
+        AppStep::PlaylistChoice => enter_url(app),
+        AppStep::ChannelFilters if app.input_mode => {
+            app.input_mode = false;
+            app.filter_error = None;
+        }
+        AppStep::ChannelFilters => {
+            app.filters = None;
+            enter_url(app);
+        }
+        AppStep::SelectFormat => match &app.parsed_url {
+            Some(parsed) if matches!(parsed.kind, UrlKind::Channel { .. }) => enter_channel_filters(app),
+            Some(parsed) if matches!(parsed.kind, UrlKind::VideoInPlaylist { .. }) => {
+                let whole_playlist = parsed.playlist().as_deref() == Some(app.url.as_str());
+                app.status = "This video is part of a playlist. Download just the video or the whole playlist?".to_string();
+                app.step = AppStep::PlaylistChoice;
+                app.list_state.select(Some(if whole_playlist { 1 } else { 0 }));
+            }
+            _ => enter_url(app),
+        },
+        AppStep::Confirm => select_format(app),
+        _ => return false,
+    }
+    true
+}
+
+fn enter_url(app: &mut AppState) {
+    let type_name = app.download_type.map(job::type_name).unwrap_or("Unknown");
+    app.status = format!("{} selected. Enter YouTube URL", type_name);
+    app.step = AppStep::EnterUrl;
+    app.input_mode = true;
+}
+
+fn open_history(app: &mut AppState, paths: &Paths) {
+    app.reset();
+    app.history = HistoryView::new(history::load(&paths.history_file));
+    app.step = AppStep::History;
+    app.status = format!("{} past downloads", app.history.entries.len());
+}
+
+fn handle_history_key(
+    app: &mut AppState,
+    key: KeyEvent,
+    paths: &Paths,
+) -> Result<bool, Box<dyn Error>> {
+    if app.history.searching {
+        match key.code {
+            KeyCode::Char(c) => app.history.search.push(c),
+            KeyCode::Backspace => {
+                app.history.search.pop();
+            }
+            KeyCode::Enter | KeyCode::Esc => app.history.searching = false,
+            _ => {}
+        }
+        app.list_state.select(Some(0));
+        return Ok(false);
+    }
+
+    let selected = app
+        .list_state
+        .selected()
+        .and_then(|i| app.history.visible().get(i).copied());
+
+    match app.keymap.action(&key) {
+        Some(Action::Quit) => app.confirm_quit = true,
+        Some(Action::Help) => app.show_help = true,
+        Some(Action::Cancel | Action::Back) => app.reset(),
+        Some(Action::Up) => move_selection_up(app),
+        Some(Action::Down) => move_selection_down(app),
+        Some(Action::Search) => app.history.searching = true,
+        Some(Action::Filter) => {
+            app.history.filter = app.history.filter.next();
+            app.list_state.select(Some(0));
+        }
+        Some(Action::Select) => {
+            if let Some(index) = selected {
+                redownload(app, index);
+            }
+        }
+        Some(Action::OpenFolder) => {
+            if let Some(path) = selected.and_then(|i| app.history.entries[i].output_path.clone()) {
+                if let Err(e) = opener::open_containing_folder(&path) {
+                    app.status = format!("Could not open folder: {}", e);
+                }
+            }
+        }
+        Some(Action::DeleteFile) => {
+            if let Some(index) = selected {
+                delete_history_file(app, index, paths)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(false)
+}
+
+fn open_subscriptions(app: &mut AppState, paths: &Paths) {
+    app.reset();
+    app.subscriptions = subscriptions::load(&paths.subscriptions_file);
+    app.step = AppStep::Subscriptions;
+    app.status = if app.subscriptions.is_empty() {
+        "No subscriptions yet. Choose Subscribe when confirming a channel or playlist download".to_string()
+    } else {
+        format!("{} subscriptions", app.subscriptions.len())
+    };
+}
+
+fn handle_subscriptions_key(
+    app: &mut AppState,
+    key: KeyEvent,
+    paths: &Paths,
+    config: &Config,
+) -> Result<bool, Box<dyn Error>> {
+    let selected = app.list_state.selected().filter(|i| *i < app.subscriptions.len());
+
+    match app.keymap.action(&key) {
+        Some(Action::Quit) => app.confirm_quit = true,
+        Some(Action::Help) => app.show_help = true,
+        Some(Action::Cancel | Action::Back) => app.reset(),
+        Some(Action::Up) => move_selection_up(app),
+        Some(Action::Down) => move_selection_down(app),
+        Some(Action::Select) => handle_enter(app, paths, config)?,
+        Some(Action::SyncAll) if !app.subscriptions.is_empty() => {
+            let all = app.subscriptions.clone();
+            sync_subscriptions(app, &all, paths, config)?;
+        }
+        Some(Action::DeleteFile) => {
+            if let Some(index) = selected {
+                let name = app.subscriptions.remove(index).name;
+                subscriptions::remove(&paths.subscriptions_file, &name)?;
+                let last = app.subscriptions.len().saturating_sub(1);
+                app.list_state.select(Some(index.min(last)));
+                app.status = format!("Unsubscribed from '{}'", name);
+            }
+        }
+        _ => {}
+    }
+    Ok(false)
+}
+
+fn open_batch_import(app: &mut AppState, paths: &Paths) {
+    app.reset();
+    app.batch_files = batch::import_files(&paths.base_dir);
+    app.step = AppStep::BatchImport;
+    app.status = "Pick a text file of URLs, one per line, or an exported queue, or type its path".to_string();
+}
+
+fn handle_batch_key(
+    app: &mut AppState,
+    key: KeyEvent,
+    paths: &Paths,
+    config: &Config,
+) -> Result<bool, Box<dyn Error>> {
+    // The path field takes typed characters, so only the arrow keys move through the files
+    match key.code {
+        KeyCode::Up => move_selection_up(app),
+        KeyCode::Down => move_selection_down(app),
+        _ => match app.keymap.action(&key) {
+            Some(Action::Select) => handle_enter(app, paths, config)?,
+            Some(Action::Cancel) => app.reset(),
+            Some(Action::Paste) => paste_from_clipboard(app),
+            _ => {
+                if app.batch_input.handle_key(key) {
+                    app.batch_errors.clear();
+                }
+            }
+        },
+    }
+    Ok(false)
+}
+
+/// Checks every line of the chosen file and queues them all, or lists the invalid lines.
+fn import_batch(app: &mut AppState, paths: &Paths, config: &Config) -> Result<(), Box<dyn Error>> {
+    let path = if app.batch_input.is_empty() {
+        match app.list_state.selected().and_then(|i| app.batch_files.get(i)) {
+            Some(file) => file.clone(),
+            None => return Ok(()),
+        }
+    } else {
+        paths.base_dir.join(app.batch_input.value())
+    };
+
+    match batch::load(&path, 0, 0) {
+        Ok(jobs) => {
+            app.reset();
+            app.queue = jobs.into();
+            start_next_job(app, paths, config)?;
+        }
+        Err(errors) => {
+            app.status = format!("{} problem(s) found, nothing was queued", errors.len());
+            app.batch_errors = errors;
+        }
+    }
+    Ok(())
+}
+
+/// Writes the running, queued and failed jobs to the export file.
+fn export_queue(app: &mut AppState, paths: &Paths) -> Result<(), Box<dyn Error>> {
+    let mut jobs: Vec<Job> = app
+        .results
+        .iter()
+        .filter(|(_, outcome)| *outcome == Outcome::Failed)
+        .map(|(job, _)| job.clone())
+        .collect();
+    if app.is_downloading() {
+        jobs.extend(app.current_job());
+    }
+    jobs.extend(app.queue.iter().cloned());
+    if jobs.is_empty() {
+        app.status = "Nothing to export: no downloads are pending or failed".to_string();
+        return Ok(());
+    }
+
+    let path = paths.base_dir.join(queue::EXPORT_FILE);
+    queue::export(&path, &jobs)?;
+    app.status = format!("Exported {} job(s) to {}", jobs.len(), path.display());
+    Ok(())
+}
+
+/// Queues a download of everything new for each subscription.
+fn sync_subscriptions(
+    app: &mut AppState,
+    selected: &[subscriptions::Subscription],
+    paths: &Paths,
+    config: &Config,
+) -> Result<(), Box<dyn Error>> {
+    std::fs::create_dir_all(&paths.archive_dir)?;
+    app.reset();
+    app.queue = selected.iter().map(|s| s.job(&paths.archive_dir)).collect();
+    start_next_job(app, paths, config)?;
+    Ok(())
+}
+
+/// Saves the job being confirmed as a subscription and downloads it with the subscription's archive.
+fn subscribe(app: &mut AppState, paths: &Paths, config: &Config) -> Result<(), Box<dyn Error>> {
+    let Some(job) = app.current_job() else {
+        return Ok(());
+    };
+    let subscription = match subscriptions::add(&paths.subscriptions_file, &subscriptions::default_name(&job.url), &job) {
+        Ok(subscription) => subscription,
+        Err(e) => {
+            app.status = e.to_string();
+            return Ok(());
+        }
+    };
+    std::fs::create_dir_all(&paths.archive_dir)?;
+    queue_pasted_urls(app);
+    app.load_job(subscription.job(&paths.archive_dir));
+    start_download(app, paths, config)?;
+    app.status = format!("Subscribed as '{}'. {}", subscription.name, app.status);
+    Ok(())
+}
+
+/// Loads a history entry's settings into the wizard, ready to confirm.
+fn redownload(app: &mut AppState, index: usize) {
+    let job = app.history.entries[index].job.clone();
+    app.reset();
+    app.load_job(job);
+    app.status = "Press Enter to start download, or Esc to go back".to_string();
+    app.step = AppStep::Confirm;
+}
+
+fn delete_history_file(app: &mut AppState, index: usize, paths: &Paths) -> Result<(), Box<dyn Error>> {
+    let entry = &mut app.history.entries[index];
+    let path = match &entry.output_path {
+        Some(path) if !entry.deleted => path.clone(),
+        _ => return Ok(()),
+    };
+
+    if path.exists() {
+        if let Err(e) = std::fs::remove_file(&path) {
+            app.status = format!("Could not delete {}: {}", path.display(), e);
+            return Ok(());
+        }
+    }
+    entry.deleted = true;
+    history::save(&paths.history_file, &app.history.entries)?;
+    app.status = format!("Deleted {}", path.display());
+    Ok(())
+}
+
 fn move_selection_up(app: &mut AppState) {
     if let Some(selected) = app.list_state.selected() {
         let options_len = app.get_current_options_len();
@@ -56,44 +631,85 @@ fn move_selection_down(app: &mut AppState) {
     }
 }
 
+fn retry_download(
+    app: &mut AppState,
+    paths: &Paths,
+    config: &Config,
+) -> Result<(), Box<dyn Error>> {
+    app.results.pop();
+    start_download(app, paths, config)
+}
+
+fn download_failed(app: &AppState) -> bool {
+    app.download_progress
+        .lock()
+        .map(|p| p.outcome == Some(Outcome::Failed))
+        .unwrap_or(false)
+}
+
 fn handle_enter(
     app: &mut AppState,
-    ytdlp_path: &std::path::PathBuf,
-    output_dir: &std::path::PathBuf,
+    paths: &Paths,
+    config: &Config,
 ) -> Result<(), Box<dyn Error>> {
     match app.step {
+        AppStep::Resume => {
+            let interrupted = app.interrupted.remove(0);
+            match app.list_state.selected() {
+                // yt-dlp's --continue (the `c` in -ciw) picks the .part files back up
+                Some(0) => start_download(app, paths, config)?,
+                Some(1) => {
+                    let removed = resume::remove_partial_files(&interrupted.job.output_dir(&paths.output_dir), interrupted.started_at)?;
+                    resume::mark_finished(&paths.jobs_file, &interrupted.job)?;
+                    app.next_interrupted();
+                    if app.step == AppStep::SelectType {
+                        app.status = format!("Removed {} partial file(s). Select download type", removed);
+                    }
+                }
+                _ => app.next_interrupted(),
+            }
+        }
         AppStep::SelectType => {
-            app.download_type = app.list_state.selected();
-            let type_name = match app.download_type {
-                Some(0) => "Video",
-                Some(1) => "Audio",
-                Some(2) => "Subtitles",
-                _ => "Unknown",
-            };
-            app.status = format!("{} selected. Enter YouTube URL", type_name);
-            app.step = AppStep::EnterUrl;
-            app.input_mode = true;
+            let selected = app.list_state.selected();
+            if selected != app.download_type {
+                // Format indices differ per type, so an earlier choice no longer applies
+                app.format = None;
+            }
+            app.download_type = selected;
+            enter_url(app);
         }
-        AppStep::EnterUrl => {
-            if !app.url.is_empty() {
-                app.input_mode = false;
-                app.status = "Select output format using arrow keys".to_string();
-                app.step = AppStep::SelectFormat;
-                app.list_state.select(Some(0));
+        AppStep::EnterUrl => accept_url(app),
+        AppStep::PlaylistChoice => {
+            if let Some(parsed) = app.parsed_url.clone() {
+                app.url = match app.list_state.selected() {
+                    Some(1) => parsed.playlist().unwrap_or_else(|| parsed.normalized()),
+                    _ => parsed.normalized(),
+                };
+                select_format(app);
+            }
+        }
+        AppStep::ChannelFilters => handle_channel_filters_enter(app, paths),
+        AppStep::BatchImport => import_batch(app, paths, config)?,
+        AppStep::Subscriptions => {
+            if let Some(subscription) = app.list_state.selected().and_then(|i| app.subscriptions.get(i)).cloned() {
+                sync_subscriptions(app, &[subscription], paths, config)?;
             }
         }
         AppStep::SelectFormat => {
             app.format = app.list_state.selected();
-            app.status = "Press Enter to start download, or 'q' to cancel".to_string();
+            app.status = "Press Enter to start download, or Esc to go back".to_string();
             app.step = AppStep::Confirm;
             app.list_state.select(Some(0));
         }
         AppStep::Confirm => {
             if let Some(selected) = app.list_state.selected() {
-                if selected == 0 {
-                    start_download(app, ytdlp_path, output_dir);
-                } else {
-                    app.reset();
+                match selected {
+                    0 => {
+                        queue_pasted_urls(app);
+                        start_download(app, paths, config)?;
+                    }
+                    2 => subscribe(app, paths, config)?,
+                    _ => app.reset(),
                 }
             }
         }
@@ -102,28 +718,192 @@ fn handle_enter(
     Ok(())
 }
 
+/// Validates the entered (and any queued) URLs before moving on to the format step.
+fn accept_url(app: &mut AppState) {
+    let parsed = match url::parse(app.url_input.value()) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            app.url_error = Some(e);
+            return;
+        }
+    };
+
+    let mut queued = Vec::new();
+    for (i, raw) in app.queued_urls.iter().enumerate() {
+        match url::parse(raw) {
+            Ok(queued_url) => queued.push(queued_url.normalized()),
+            Err(e) => {
+                app.url_error = Some(format!("Queued URL {} ({}): {}", i + 1, raw, e));
+                return;
+            }
+        }
+    }
+    app.queued_urls = queued;
+
+    app.url_input.push_history(&parsed.normalized());
+    app.url_error = None;
+    app.input_mode = false;
+
+    app.url = parsed.normalized();
+    let kind = parsed.kind.clone();
+    app.parsed_url = Some(parsed);
+    match kind {
+        UrlKind::VideoInPlaylist { .. } => {
+            app.filters = None;
+            app.status = "This video is part of a playlist. Download just the video or the whole playlist?".to_string();
+            app.step = AppStep::PlaylistChoice;
+            app.list_state.select(Some(0));
+        }
+        UrlKind::Channel { .. } => enter_channel_filters(app),
+        _ => {
+            app.filters = None;
+            select_format(app);
+        }
+    }
+}
+
+fn enter_channel_filters(app: &mut AppState) {
+    app.status = "Channel: narrow down which videos to download, preview the matches, then continue".to_string();
+    app.step = AppStep::ChannelFilters;
+    app.input_mode = false;
+    app.filter_error = None;
+    app.list_state.select(Some(0));
+}
+
+/// Rows of the channel step: the tab, one per filter, then "Preview" and "Continue".
+const TAB_ROW: usize = 0;
+const PREVIEW_ROW: usize = channel::FIELDS.len() + 1;
+const CONTINUE_ROW: usize = channel::FIELDS.len() + 2;
+
+fn handle_channel_filters_enter(app: &mut AppState, paths: &Paths) {
+    let Some(selected) = app.list_state.selected() else {
+        return;
+    };
+    match selected {
+        TAB_ROW => {
+            if let Some(parsed) = &app.parsed_url {
+                let current = url::VIDEO_TABS.iter().position(|t| *t == parsed.channel_tab()).unwrap_or(0);
+                let next = url::VIDEO_TABS[(current + 1) % url::VIDEO_TABS.len()];
+                let parsed = parsed.with_channel_tab(next);
+                app.url = parsed.normalized();
+                app.parsed_url = Some(parsed);
+                app.channel_preview = Arc::new(Mutex::new(Preview::NotRun));
+            }
+        }
+        PREVIEW_ROW => start_preview(app, paths),
+        CONTINUE_ROW => {
+            let filters = app.channel_filters.clone();
+            app.filters = if filters.is_empty() { None } else { Some(filters) };
+            select_format(app);
+            if let Some(filters) = &app.filters {
+                app.status = format!("Filters: {}. Select output format", filters.summary());
+            }
+        }
+        row => {
+            let field = channel::FIELDS[row - 1];
+            app.filter_input.set_value(&field.value(&app.channel_filters));
+            app.filter_error = None;
+            app.input_mode = true;
+        }
+    }
+}
+
+fn save_filter_input(app: &mut AppState) {
+    let Some(row) = app.list_state.selected() else {
+        return;
+    };
+    let field = channel::FIELDS[row - 1];
+    match field.set(&mut app.channel_filters, app.filter_input.value()) {
+        Ok(()) => {
+            app.input_mode = false;
+            app.filter_error = None;
+            app.channel_preview = Arc::new(Mutex::new(Preview::NotRun));
+        }
+        Err(e) => app.filter_error = Some(e),
+    }
+}
+
+/// Counts the matching videos in the background; the result shows up in the channel step.
+fn start_preview(app: &mut AppState, paths: &Paths) {
+    let preview = Arc::new(Mutex::new(Preview::Running));
+    app.channel_preview = preview.clone();
+
+    let ytdlp_path = paths.ytdlp.clone();
+    let url = app.url.clone();
+    let filters = app.channel_filters.clone();
+    let events = app.download_progress.lock().ok().and_then(|p| p.events.clone());
+    thread::spawn(move || {
+        let result = match channel::count_matches(&ytdlp_path, &url, &filters) {
+            Ok(count) => Preview::Matches(count),
+            Err(e) => Preview::Failed(e),
+        };
+        if let Ok(mut preview) = preview.lock() {
+            *preview = result;
+        }
+        if let Some(events) = events {
+            let _ = events.send(AppEvent::Progress);
+        }
+    });
+}
+
+fn select_format(app: &mut AppState) {
+    app.status = "Select output format using arrow keys".to_string();
+    app.step = AppStep::SelectFormat;
+    app.list_state.select(Some(app.format.unwrap_or(0)));
+}
+
+fn queue_pasted_urls(app: &mut AppState) {
+    if let (Some(download_type), Some(format)) = (app.download_type, app.format) {
+        let urls = std::mem::take(&mut app.queued_urls);
+        let filters = app.filters.clone();
+        app.queue = urls
+            .into_iter()
+            .map(|url| {
+                // Channel filters only make sense for the other channels in the batch
+                let is_channel = url::parse(&url).is_ok_and(|u| matches!(u.kind, UrlKind::Channel { .. }));
+                let filters = if is_channel { filters.clone() } else { None };
+                Job { filters, ..Job::new(url, download_type, format) }
+            })
+            .collect();
+    }
+    app.results.clear();
+}
+
 fn start_download(
     app: &mut AppState,
-    ytdlp_path: &std::path::PathBuf,
-    output_dir: &std::path::PathBuf,
-) {
+    paths: &Paths,
+    config: &Config,
+) -> Result<(), Box<dyn Error>> {
+    let job = match app.current_job() {
+        Some(job) => job,
+        None => return Ok(()),
+    };
+    resume::mark_started(&paths.jobs_file, &job)?;
+
     app.step = AppStep::Downloading;
-    app.status = "Downloading... Please wait".to_string();
+    let total = app.results.len() + 1 + app.queue.len();
+    app.status = if total > 1 {
+        format!("Downloading {} of {}... Please wait", app.results.len() + 1, total)
+    } else {
+        "Downloading... Please wait".to_string()
+    };
     
     {
         let mut progress = app.download_progress.lock().unwrap();
         progress.active = true;
+        progress.outcome = None;
+        progress.files.clear();
+        progress.cancel_requested = false;
         progress.message = "Initializing download...".to_string();
     }
     
-    let ytdlp_path = ytdlp_path.clone();
-    let output_dir = output_dir.clone();
-    let download_type = app.download_type.unwrap();
-    let format = app.format.unwrap();
-    let url = app.url.clone();
+    let ytdlp_path = paths.ytdlp.clone();
+    let output_dir = paths.output_dir.clone();
+    let config = config.clone();
     let progress_clone = app.download_progress.clone();
     
-    thread::spawn(move || {
-        run_download_thread(download_type, format, &url, &ytdlp_path, &output_dir, progress_clone)
-    });
+    app.download_thread = Some(thread::spawn(move || {
+        run_download_thread(&job, &ytdlp_path, &output_dir, &config, progress_clone)
+    }));
+    Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,181 @@
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use crate::download::DownloadedFile;
+use crate::job::{self, Job};
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub enum Outcome {
+    Success,
+    NoSubtitles,
+    Failed,
+    Cancelled,
+}
+
+/// One finished download, or one produced file for playlist downloads.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct HistoryEntry {
+    pub job: Job,
+    pub title: Option<String>,
+    pub output_path: Option<PathBuf>,
+    pub size: Option<u64>,
+    pub duration: Option<f64>,
+    pub outcome: Outcome,
+    pub timestamp: u64,
+    #[serde(default)]
+    pub deleted: bool,
+}
+
+impl HistoryEntry {
+    /// Builds one entry per produced file, or a single entry if nothing was produced.
+    pub fn from_result(job: &Job, outcome: Outcome, files: &[DownloadedFile]) -> Vec<HistoryEntry> {
+        let timestamp = job::unix_time();
+        if files.is_empty() {
+            return vec![HistoryEntry {
+                job: job.clone(),
+                title: None,
+                output_path: None,
+                size: None,
+                duration: None,
+                outcome,
+                timestamp,
+                deleted: false,
+            }];
+        }
+        files
+            .iter()
+            .map(|file| HistoryEntry {
+                job: job.clone(),
+                title: Some(file.title.clone()),
+                output_path: Some(file.path.clone()),
+                size: file.size,
+                duration: file.duration,
+                outcome,
+                timestamp,
+                deleted: false,
+            })
+            .collect()
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum HistoryFilter {
+    All,
+    Succeeded,
+    Failed,
+    Video,
+    Audio,
+    Subtitles,
+}
+
+impl HistoryFilter {
+    pub fn next(self) -> Self {
+        match self {
+            HistoryFilter::All => HistoryFilter::Succeeded,
+            HistoryFilter::Succeeded => HistoryFilter::Failed,
+            HistoryFilter::Failed => HistoryFilter::Video,
+            HistoryFilter::Video => HistoryFilter::Audio,
+            HistoryFilter::Audio => HistoryFilter::Subtitles,
+            HistoryFilter::Subtitles => HistoryFilter::All,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            HistoryFilter::All => "All",
+            HistoryFilter::Succeeded => "Succeeded",
+            HistoryFilter::Failed => "Failed",
+            HistoryFilter::Video => "Video",
+            HistoryFilter::Audio => "Audio",
+            HistoryFilter::Subtitles => "Subtitles",
+        }
+    }
+
+    fn matches(self, entry: &HistoryEntry) -> bool {
+        match self {
+            HistoryFilter::All => true,
+            HistoryFilter::Succeeded => entry.outcome == Outcome::Success,
+            HistoryFilter::Failed => entry.outcome != Outcome::Success,
+            HistoryFilter::Video => entry.job.download_type == 0,
+            HistoryFilter::Audio => entry.job.download_type == 1,
+            HistoryFilter::Subtitles => entry.job.download_type == 2,
+        }
+    }
+}
+
+/// State of the History screen: all entries plus the active search and filter.
+pub struct HistoryView {
+    pub entries: Vec<HistoryEntry>,
+    pub search: String,
+    pub searching: bool,
+    pub filter: HistoryFilter,
+}
+
+impl HistoryView {
+    pub fn new(entries: Vec<HistoryEntry>) -> Self {
+        HistoryView {
+            entries,
+            search: String::new(),
+            searching: false,
+            filter: HistoryFilter::All,
+        }
+    }
+
+    /// Indices into `entries` of the visible entries, newest first.
+    pub fn visible(&self) -> Vec<usize> {
+        let search = self.search.to_lowercase();
+        (0..self.entries.len())
+            .rev()
+            .filter(|&i| {
+                let entry = &self.entries[i];
+                self.filter.matches(entry) && (search.is_empty() || entry_text(entry).contains(&search))
+            })
+            .collect()
+    }
+}
+
+fn entry_text(entry: &HistoryEntry) -> String {
+    let mut text = format!(
+        "{} {} {}",
+        entry.job.url,
+        job::type_name(entry.job.download_type),
+        job::format_name(entry.job.download_type, entry.job.format),
+    );
+    if let Some(title) = &entry.title {
+        text.push(' ');
+        text.push_str(title);
+    }
+    if let Some(path) = &entry.output_path {
+        text.push(' ');
+        text.push_str(&path.to_string_lossy());
+    }
+    text.to_lowercase()
+}
+
+pub fn load(path: &Path) -> Vec<HistoryEntry> {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+/// Distinct URLs from the history, oldest first, for recalling in the URL field.
+pub fn recent_urls(entries: &[HistoryEntry]) -> Vec<String> {
+    let mut urls: Vec<String> = Vec::new();
+    for entry in entries {
+        urls.retain(|url| url != &entry.job.url);
+        urls.push(entry.job.url.clone());
+    }
+    urls
+}
+
+pub fn save(path: &Path, entries: &[HistoryEntry]) -> Result<(), Box<dyn Error>> {
+    std::fs::write(path, serde_json::to_string_pretty(entries)?)?;
+    Ok(())
+}
+
+pub fn append(path: &Path, new_entries: Vec<HistoryEntry>) -> Result<(), Box<dyn Error>> {
+    let mut entries = load(path);
+    entries.extend(new_entries);
+    save(path, &entries)
+}
@@ -0,0 +1,215 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use unicode_width::UnicodeWidthChar;
+
+/// Characters that end a "word" for Ctrl+W, chosen so URLs delete one segment at a time.
+const WORD_SEPARATORS: [char; 8] = ['/', '?', '&', '=', '.', ':', '#', ' '];
+
+/// A single-line text field with a cursor and recallable history.
+pub struct TextInput {
+    value: String,
+    /// Cursor position in characters, not bytes.
+    cursor: usize,
+    history: Vec<String>,
+    history_index: Option<usize>,
+    /// What was typed before browsing history, restored when scrolling back down.
+    draft: String,
+}
+
+impl TextInput {
+    pub fn new() -> Self {
+        TextInput {
+            value: String::new(),
+            cursor: 0,
+            history: Vec::new(),
+            history_index: None,
+            draft: String::new(),
+        }
+    }
+
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.value.is_empty()
+    }
+
+    pub fn set_value(&mut self, value: &str) {
+        self.value = value.to_string();
+        self.cursor = self.value.chars().count();
+        self.history_index = None;
+    }
+
+    pub fn take_history(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.history)
+    }
+
+    pub fn set_history(&mut self, history: Vec<String>) {
+        self.history = history;
+    }
+
+    pub fn push_history(&mut self, entry: &str) {
+        if entry.is_empty() || self.history.last().map(String::as_str) == Some(entry) {
+            return;
+        }
+        self.history.retain(|h| h != entry);
+        self.history.push(entry.to_string());
+    }
+
+    /// Applies an editing key, returning false if the key isn't an editing key.
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Char('w') if ctrl => self.delete_word_before(),
+            KeyCode::Char('u') if ctrl => self.delete_to_start(),
+            KeyCode::Char('a') if ctrl => self.cursor = 0,
+            KeyCode::Char('e') if ctrl => self.cursor = self.len(),
+            KeyCode::Char(c) if !ctrl => self.insert(c),
+            KeyCode::Backspace => self.backspace(),
+            KeyCode::Delete => self.delete(),
+            KeyCode::Left if ctrl => self.cursor = self.word_start_before(self.cursor),
+            KeyCode::Right if ctrl => self.cursor = self.word_end_after(self.cursor),
+            KeyCode::Left => self.cursor = self.cursor.saturating_sub(1),
+            KeyCode::Right => self.cursor = (self.cursor + 1).min(self.len()),
+            KeyCode::Home => self.cursor = 0,
+            KeyCode::End => self.cursor = self.len(),
+            KeyCode::Up => self.history_prev(),
+            KeyCode::Down => self.history_next(),
+            _ => return false,
+        }
+        true
+    }
+
+    pub fn insert(&mut self, c: char) {
+        let at = self.byte_index(self.cursor);
+        self.value.insert(at, c);
+        self.cursor += 1;
+    }
+
+    pub fn insert_str(&mut self, text: &str) {
+        let at = self.byte_index(self.cursor);
+        self.value.insert_str(at, text);
+        self.cursor += text.chars().count();
+    }
+
+    fn backspace(&mut self) {
+        if self.cursor > 0 {
+            self.remove_range(self.cursor - 1, self.cursor);
+        }
+    }
+
+    fn delete(&mut self) {
+        if self.cursor < self.len() {
+            self.remove_range(self.cursor, self.cursor + 1);
+        }
+    }
+
+    fn delete_word_before(&mut self) {
+        let start = self.word_start_before(self.cursor);
+        self.remove_range(start, self.cursor);
+    }
+
+    fn delete_to_start(&mut self) {
+        self.remove_range(0, self.cursor);
+    }
+
+    fn history_prev(&mut self) {
+        if self.history.is_empty() {
+            return;
+        }
+        let index = match self.history_index {
+            None => {
+                self.draft = self.value.clone();
+                self.history.len() - 1
+            }
+            Some(0) => 0,
+            Some(i) => i - 1,
+        };
+        self.recall(Some(index));
+    }
+
+    fn history_next(&mut self) {
+        match self.history_index {
+            None => {}
+            Some(i) if i + 1 < self.history.len() => self.recall(Some(i + 1)),
+            Some(_) => self.recall(None),
+        }
+    }
+
+    fn recall(&mut self, index: Option<usize>) {
+        let value = match index {
+            Some(i) => self.history[i].clone(),
+            None => std::mem::take(&mut self.draft),
+        };
+        self.set_value(&value);
+        self.history_index = index;
+    }
+
+    /// Returns the part of the value that fits in `width` columns and the cursor's column in it.
+    pub fn visible(&self, width: usize) -> (String, usize) {
+        let chars: Vec<char> = self.value.chars().collect();
+        let char_width = |c: &char| c.width().unwrap_or(0);
+
+        // Scroll just far enough that the cursor (plus one cell for it) stays in view
+        let mut start = 0;
+        while start < self.cursor
+            && chars[start..self.cursor].iter().map(char_width).sum::<usize>() + 1 > width
+        {
+            start += 1;
+        }
+
+        let cursor_x = chars[start..self.cursor].iter().map(char_width).sum();
+        let mut used = 0;
+        let text = chars[start..]
+            .iter()
+            .take_while(|c| {
+                used += char_width(c);
+                used <= width
+            })
+            .collect();
+        (text, cursor_x)
+    }
+
+    fn len(&self) -> usize {
+        self.value.chars().count()
+    }
+
+    fn byte_index(&self, char_index: usize) -> usize {
+        self.value
+            .char_indices()
+            .nth(char_index)
+            .map(|(i, _)| i)
+            .unwrap_or(self.value.len())
+    }
+
+    fn remove_range(&mut self, start: usize, end: usize) {
+        let (start_byte, end_byte) = (self.byte_index(start), self.byte_index(end));
+        self.value.replace_range(start_byte..end_byte, "");
+        self.cursor = start;
+        self.history_index = None;
+    }
+
+    fn word_start_before(&self, from: usize) -> usize {
+        let chars: Vec<char> = self.value.chars().collect();
+        let mut i = from;
+        while i > 0 && WORD_SEPARATORS.contains(&chars[i - 1]) {
+            i -= 1;
+        }
+        while i > 0 && !WORD_SEPARATORS.contains(&chars[i - 1]) {
+            i -= 1;
+        }
+        i
+    }
+
+    fn word_end_after(&self, from: usize) -> usize {
+        let chars: Vec<char> = self.value.chars().collect();
+        let mut i = from;
+        while i < chars.len() && WORD_SEPARATORS.contains(&chars[i]) {
+            i += 1;
+        }
+        while i < chars.len() && !WORD_SEPARATORS.contains(&chars[i]) {
+            i += 1;
+        }
+        i
+    }
+}
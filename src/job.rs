@@ -0,0 +1,111 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::channel::ChannelFilters;
+
+const TYPE_NAMES: [&str; 3] = ["Video", "Audio", "Subtitles"];
+const VIDEO_FORMATS: [&str; 3] = ["MP4", "MKV", "WebM"];
+const AUDIO_FORMATS: [&str; 5] = ["FLAC", "MP3", "WAV", "AAC", "M4A"];
+const SUBTITLE_FORMATS: [&str; 2] = ["English", "All"];
+
+/// Everything needed to run a single yt-dlp download.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Job {
+    pub url: String,
+    pub download_type: usize,
+    pub format: usize,
+    /// Only set for channel downloads that limit which videos are fetched.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filters: Option<ChannelFilters>,
+    /// yt-dlp output template; `%(title)s.%(ext)s` when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+    /// yt-dlp download archive, so videos already fetched by a subscription are skipped.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archive: Option<PathBuf>,
+    /// Folder to download into, relative to the output folder; the output folder itself when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_dir: Option<PathBuf>,
+}
+
+/// Output file name used when a job doesn't set its own template.
+pub const DEFAULT_TEMPLATE: &str = "%(title)s.%(ext)s";
+
+impl Job {
+    pub fn new(url: String, download_type: usize, format: usize) -> Self {
+        Job {
+            url,
+            download_type,
+            format,
+            filters: None,
+            template: None,
+            archive: None,
+            output_dir: None,
+        }
+    }
+
+    pub fn template(&self) -> &str {
+        self.template.as_deref().unwrap_or(DEFAULT_TEMPLATE)
+    }
+
+    /// Where the job's files go, given the app's output folder.
+    pub fn output_dir(&self, default: &Path) -> PathBuf {
+        match &self.output_dir {
+            Some(dir) => default.join(dir),
+            None => default.to_path_buf(),
+        }
+    }
+}
+
+pub fn type_name(download_type: usize) -> &'static str {
+    TYPE_NAMES.get(download_type).copied().unwrap_or("Unknown")
+}
+
+pub fn format_name(download_type: usize, format: usize) -> &'static str {
+    formats(download_type).get(format).copied().unwrap_or("Unknown")
+}
+
+/// Looks up a download type by name, e.g. "audio".
+pub fn parse_type(name: &str) -> Option<usize> {
+    TYPE_NAMES.iter().position(|t| t.eq_ignore_ascii_case(name))
+}
+
+/// Looks up a format of the download type by name, e.g. "mp3".
+pub fn parse_format(download_type: usize, name: &str) -> Option<usize> {
+    formats(download_type).iter().position(|f| f.eq_ignore_ascii_case(name))
+}
+
+/// Looks up a type and one of its formats by name; the type's first format if none is given.
+pub fn parse_settings(type_name: &str, format_name: Option<&str>) -> Result<(usize, usize), String> {
+    let download_type = parse_type(type_name)
+        .ok_or_else(|| format!("Unknown type '{}' (video, audio or subtitles)", type_name))?;
+    let format = match format_name {
+        Some(name) => parse_format(download_type, name).ok_or_else(|| {
+            format!("Unknown {} format '{}' (one of {})", type_name.to_lowercase(), name, format_names(download_type))
+        })?,
+        None => 0,
+    };
+    Ok((download_type, format))
+}
+
+pub fn format_names(download_type: usize) -> String {
+    formats(download_type).join(", ").to_lowercase()
+}
+
+fn formats(download_type: usize) -> &'static [&'static str] {
+    match download_type {
+        0 => &VIDEO_FORMATS,
+        1 => &AUDIO_FORMATS,
+        2 => &SUBTITLE_FORMATS,
+        _ => &[],
+    }
+}
+
+/// Seconds since the Unix epoch, used to timestamp jobs.
+pub fn unix_time() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
@@ -0,0 +1,250 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::Deserialize;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::config::Config;
+
+/// Something the user can do with a key, independent of which key it is.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    Up,
+    Down,
+    Select,
+    Back,
+    Cancel,
+    Quit,
+    Restart,
+    Retry,
+    History,
+    Search,
+    Filter,
+    OpenFolder,
+    DeleteFile,
+    Paste,
+    Help,
+    Subscriptions,
+    SyncAll,
+    Import,
+    Export,
+}
+
+impl Action {
+    /// All actions, in the order the help overlay lists them.
+    pub const ALL: [Action; 19] = [
+        Action::Up,
+        Action::Down,
+        Action::Select,
+        Action::Back,
+        Action::Cancel,
+        Action::Quit,
+        Action::Restart,
+        Action::Retry,
+        Action::History,
+        Action::Search,
+        Action::Filter,
+        Action::OpenFolder,
+        Action::DeleteFile,
+        Action::Paste,
+        Action::Help,
+        Action::Subscriptions,
+        Action::SyncAll,
+        Action::Import,
+        Action::Export,
+    ];
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Action::Up => "Move up",
+            Action::Down => "Move down",
+            Action::Select => "Select / confirm",
+            Action::Back => "Previous step",
+            Action::Cancel => "Go back, or quit on the first step",
+            Action::Quit => "Quit",
+            Action::Restart => "Start over after downloading",
+            Action::Retry => "Retry a failed download",
+            Action::History => "Open download history",
+            Action::Search => "Search history",
+            Action::Filter => "Cycle history filter",
+            Action::OpenFolder => "Open the file's folder",
+            Action::DeleteFile => "Delete the downloaded file, or the subscription",
+            Action::Paste => "Paste URLs from the clipboard",
+            Action::Help => "Show all key bindings",
+            Action::Subscriptions => "Open subscriptions",
+            Action::SyncAll => "Sync all subscriptions",
+            Action::Import => "Import URLs from a text file or exported queue",
+            Action::Export => "Export pending and failed downloads",
+        }
+    }
+
+    fn default_keys(self) -> &'static [&'static str] {
+        match self {
+            Action::Up => &["up", "k"],
+            Action::Down => &["down", "j"],
+            Action::Select => &["enter"],
+            Action::Back => &["backspace", "left"],
+            Action::Cancel => &["esc"],
+            Action::Quit => &["q"],
+            Action::Restart => &["r"],
+            Action::Retry => &["t"],
+            Action::History => &["h"],
+            Action::Search => &["/"],
+            Action::Filter => &["f"],
+            Action::OpenFolder => &["o"],
+            Action::DeleteFile => &["d"],
+            Action::Paste => &["ctrl+v"],
+            Action::Help => &["?"],
+            Action::Subscriptions => &["s"],
+            Action::SyncAll => &["a"],
+            Action::Import => &["i"],
+            Action::Export => &["e"],
+        }
+    }
+}
+
+/// A key plus the modifiers that must be held, e.g. `ctrl+v`.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct KeyBinding {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl KeyBinding {
+    pub fn matches(&self, key: &KeyEvent) -> bool {
+        // Shift is already part of the character for keys like `?` or `Q`
+        let significant = |modifiers: KeyModifiers| match self.code {
+            KeyCode::Char(_) => modifiers - KeyModifiers::SHIFT,
+            _ => modifiers,
+        };
+        self.code == key.code && significant(self.modifiers) == significant(key.modifiers)
+    }
+}
+
+impl FromStr for KeyBinding {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let (prefix, key) = match text.rsplit_once('+') {
+            // A trailing "+" is the plus key itself, e.g. "+" or "ctrl++"
+            Some((prefix, "")) => (prefix.trim_end_matches('+'), "+"),
+            Some((prefix, key)) => (prefix, key),
+            None => ("", text),
+        };
+
+        let mut modifiers = KeyModifiers::NONE;
+        for modifier in prefix.split('+').filter(|m| !m.is_empty()) {
+            modifiers |= match modifier.to_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => return Err(format!("Unknown modifier '{}' in key '{}'", modifier, text)),
+            };
+        }
+
+        let mut chars = key.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match key.to_lowercase().as_str() {
+                "enter" | "return" => KeyCode::Enter,
+                "esc" | "escape" => KeyCode::Esc,
+                "backspace" => KeyCode::Backspace,
+                "tab" => KeyCode::Tab,
+                "space" => KeyCode::Char(' '),
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                "delete" | "del" => KeyCode::Delete,
+                "insert" => KeyCode::Insert,
+                name => match name.strip_prefix('f').and_then(|n| n.parse().ok()) {
+                    Some(n @ 1..=12) => KeyCode::F(n),
+                    _ => return Err(format!("Unknown key '{}'", text)),
+                },
+            },
+        };
+        Ok(KeyBinding { code, modifiers })
+    }
+}
+
+impl fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "Ctrl+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "Alt+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            write!(f, "Shift+")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) if self.modifiers.is_empty() => write!(f, "{}", c),
+            KeyCode::Char(c) => write!(f, "{}", c.to_ascii_uppercase()),
+            KeyCode::F(n) => write!(f, "F{}", n),
+            KeyCode::PageUp => write!(f, "PgUp"),
+            KeyCode::PageDown => write!(f, "PgDn"),
+            code => write!(f, "{:?}", code),
+        }
+    }
+}
+
+/// Which keys trigger which actions; defaults can be overridden per action in `[keys]`.
+#[derive(Clone, Debug)]
+pub struct Keymap {
+    bindings: Vec<(Action, Vec<KeyBinding>)>,
+}
+
+impl Keymap {
+    pub fn from_config(config: &Config) -> Result<Self, String> {
+        let mut bindings = Vec::new();
+        for action in Action::ALL {
+            let names: Vec<&str> = match config.keys.get(&action) {
+                Some(keys) => keys.iter().map(String::as_str).collect(),
+                None => action.default_keys().to_vec(),
+            };
+            let keys = names.iter().map(|k| k.parse()).collect::<Result<_, _>>()?;
+            bindings.push((action, keys));
+        }
+
+        let keymap = Keymap { bindings };
+        for (action, keys) in &keymap.bindings {
+            for key in keys {
+                if let Some((other, _)) = keymap
+                    .bindings
+                    .iter()
+                    .find(|(other, other_keys)| other != action && other_keys.contains(key))
+                {
+                    return Err(format!("Key '{}' is bound to both {:?} and {:?}", key, action, other));
+                }
+            }
+        }
+        Ok(keymap)
+    }
+
+    /// The action bound to the key, if any.
+    pub fn action(&self, key: &KeyEvent) -> Option<Action> {
+        self.bindings
+            .iter()
+            .find(|(_, keys)| keys.iter().any(|k| k.matches(key)))
+            .map(|(action, _)| *action)
+    }
+
+    pub fn keys(&self, action: Action) -> &[KeyBinding] {
+        self.bindings
+            .iter()
+            .find(|(a, _)| *a == action)
+            .map(|(_, keys)| keys.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// The first key bound to the action, for short hints; "-" if it's unbound.
+    pub fn hint(&self, action: Action) -> String {
+        self.keys(action).first().map(|k| k.to_string()).unwrap_or_else(|| "-".to_string())
+    }
+}
@@ -1,7 +1,24 @@
 mod app;
+mod batch;
+mod channel;
+mod cli;
+mod clipboard;
+mod config;
+mod display;
 mod ui;
+mod url;
 mod download;
+mod events;
 mod handlers;
+mod history;
+mod input;
+mod job;
+mod keymap;
+mod opener;
+mod queue;
+mod resume;
+mod subscriptions;
+mod theme;
 
 use ratatui::{
     backend::CrosstermBackend,
@@ -9,68 +26,217 @@ use ratatui::{
 };
 
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event},
+    cursor::Show,
+    event::{
+        DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture, Event,
+        KeyEventKind, MouseEventKind,
+    },
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 
+use std::backtrace::Backtrace;
 use std::error::Error;
-use std::io;
+use std::io::{self, Write};
+use std::panic::PanicHookInfo;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::Mutex;
 
-use app::{AppState, AppStep};
+use app::{AppState, AppStep, ClickAreas};
+use config::{Config, Paths};
+use events::AppEvent;
+use history::{HistoryEntry, Outcome};
+use keymap::Keymap;
+use theme::Theme;
 use ui::render_ui;
-use handlers::handle_key_event;
+use handlers::{handle_key_event, handle_mouse_event, handle_paste, start_next_job};
 
-fn main() -> Result<(), Box<dyn Error>> {
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
+/// Snapshot of the app state, refreshed every frame so crash reports can include it.
+static CRASH_CONTEXT: Mutex<String> = Mutex::new(String::new());
 
-    let base_dir = std::env::current_dir()?;
-    let setup_dir = base_dir.join("setup");
-    let output_dir = base_dir.join("output");
-    let ytdlp_path = setup_dir.join("yt-dlp.exe");
+/// Puts the terminal into TUI mode and restores it when dropped, even on early `?` returns.
+struct TerminalGuard;
 
-    if !output_dir.exists() {
-        std::fs::create_dir_all(&output_dir)?;
+impl TerminalGuard {
+    fn enter() -> io::Result<Self> {
+        enable_raw_mode()?;
+        let guard = TerminalGuard;
+        execute!(io::stdout(), EnterAlternateScreen, EnableMouseCapture, EnableBracketedPaste)?;
+        Ok(guard)
     }
+}
 
-    let result = run_app(&mut terminal, ytdlp_path, output_dir);
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        restore_terminal();
+    }
+}
 
-    disable_raw_mode()?;
-    execute!(
-        terminal.backend_mut(),
+fn restore_terminal() {
+    let _ = disable_raw_mode();
+    let _ = execute!(
+        io::stdout(),
         LeaveAlternateScreen,
-        DisableMouseCapture
-    )?;
-    terminal.show_cursor()?;
-    result
+        DisableMouseCapture,
+        DisableBracketedPaste,
+        Show
+    );
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let base_dir = std::env::current_dir()?;
+    let paths = Paths::new(&base_dir);
+    let config = Config::load(&paths.config_file)?;
+    let theme = Theme::from_config(&config)?;
+    let keymap = Keymap::from_config(&config)?;
+
+    if !paths.output_dir.exists() {
+        std::fs::create_dir_all(&paths.output_dir)?;
+    }
+
+    let command = cli::parse(std::env::args().skip(1)).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(2);
+    });
+    if !matches!(command, cli::Command::Tui) {
+        if let Err(e) = cli::run(command, &paths, &config) {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    install_panic_hook(paths.crash_log.clone());
+
+    let _guard = TerminalGuard::enter()?;
+    let backend = CrosstermBackend::new(io::stdout());
+    let mut terminal = Terminal::new(backend)?;
+
+    run_app(&mut terminal, &paths, &config, theme, keymap)
+}
+
+/// Restores the terminal before a panic message is printed and writes a crash report.
+fn install_panic_hook(crash_log: PathBuf) {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        // Only the UI thread owns the terminal; a panicking download thread leaves it alone
+        if std::thread::current().name() == Some("main") {
+            restore_terminal();
+        }
+        let report_written = write_crash_report(&crash_log, info).is_ok();
+        default_hook(info);
+        if report_written {
+            eprintln!("Crash report written to {}", crash_log.display());
+        }
+    }));
+}
+
+fn write_crash_report(path: &Path, info: &PanicHookInfo) -> io::Result<()> {
+    let message = info
+        .payload()
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| info.payload().downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string());
+    let location = info
+        .location()
+        .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column()))
+        .unwrap_or_else(|| "unknown".to_string());
+    let app_state = match CRASH_CONTEXT.lock() {
+        Ok(context) => context.clone(),
+        Err(poisoned) => poisoned.into_inner().clone(),
+    };
+
+    let mut file = std::fs::OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "=== Crash at {} UTC ===", ui::format_timestamp(job::unix_time()))?;
+    writeln!(file, "Version: {}", env!("CARGO_PKG_VERSION"))?;
+    writeln!(file, "Thread: {}", std::thread::current().name().unwrap_or("unnamed"))?;
+    writeln!(file, "Panic: {}", message)?;
+    writeln!(file, "Location: {}", location)?;
+    writeln!(file, "App state:\n{}", app_state)?;
+    writeln!(file, "Backtrace:\n{}\n", Backtrace::force_capture())?;
+    Ok(())
 }
 
 fn run_app(
     terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
-    ytdlp_path: std::path::PathBuf,
-    output_dir: std::path::PathBuf,
+    paths: &Paths,
+    config: &Config,
+    theme: Theme,
+    keymap: Keymap,
 ) -> Result<(), Box<dyn Error>> {
-    let mut app = AppState::new();
+    let mut app = AppState::new(theme, keymap);
+    app.url_input.set_history(history::recent_urls(&history::load(&paths.history_file)));
+    app.interrupted = resume::load(&paths.jobs_file);
+    app.next_interrupted();
+
+    let (sender, events) = mpsc::channel();
+    events::spawn_terminal_reader(sender.clone());
+    events::spawn_ticker(sender.clone());
+    app.download_progress.lock().unwrap().events = Some(sender);
 
+    let result = event_loop(terminal, &mut app, &events, paths, config);
+
+    // Never leave yt-dlp running unattended; detaching hands it off explicitly before this
+    app.stop_download();
+    result
+}
+
+/// Redraws only after something changed, handling every queued event first.
+fn event_loop(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    app: &mut AppState,
+    events: &Receiver<AppEvent>,
+    paths: &Paths,
+    config: &Config,
+) -> Result<(), Box<dyn Error>> {
+    let mut redraw = true;
     loop {
-        terminal.draw(|f| render_ui(f, &app))?;
-        check_download_status(&mut app);
-        update_spinner(&mut app);
-
-        if event::poll(std::time::Duration::from_millis(100))? {
-            if let Event::Key(key) = event::read()? {
-                if key.kind != crossterm::event::KeyEventKind::Press {
-                    continue;
-                }
+        if redraw {
+            if let Ok(mut context) = CRASH_CONTEXT.lock() {
+                *context = app.describe();
+            }
+            let mut click_areas = ClickAreas::default();
+            terminal.draw(|f| click_areas = render_ui(f, app))?;
+            app.click_areas = click_areas;
+            redraw = false;
+        }
 
-                let should_quit = handle_key_event(&mut app, key.code, &ytdlp_path, &output_dir)?;
-                if should_quit {
-                    break;
+        if app.quit_when_done && !app.is_downloading() {
+            break;
+        }
+
+        let first = match events.recv() {
+            Ok(event) => event,
+            Err(_) => break,
+        };
+        for event in std::iter::once(first).chain(events.try_iter()) {
+            match event {
+                AppEvent::Terminal(Event::Key(key)) if key.kind == KeyEventKind::Press => {
+                    if handle_key_event(app, key, paths, config)? {
+                        return Ok(());
+                    }
+                    redraw = true;
+                }
+                AppEvent::Terminal(Event::Mouse(mouse)) => {
+                    // Plain mouse movement is reported too but never changes anything
+                    if !matches!(mouse.kind, MouseEventKind::Moved) {
+                        handle_mouse_event(app, mouse, paths, config)?;
+                        redraw = true;
+                    }
                 }
+                AppEvent::Terminal(Event::Paste(text)) => {
+                    handle_paste(app, &text);
+                    redraw = true;
+                }
+                AppEvent::Terminal(Event::Resize(..)) => redraw = true,
+                AppEvent::Terminal(_) => {}
+                AppEvent::Progress => {
+                    check_download_status(app, paths, config)?;
+                    redraw = true;
+                }
+                AppEvent::Tick => redraw |= update_spinner(app),
             }
         }
     }
@@ -78,23 +244,57 @@ fn run_app(
     Ok(())
 }
 
-fn check_download_status(app: &mut AppState) {
+fn check_download_status(
+    app: &mut AppState,
+    paths: &Paths,
+    config: &Config,
+) -> Result<(), Box<dyn Error>> {
     if app.step == AppStep::Downloading {
-        if let Ok(progress) = app.download_progress.lock() {
-            if !progress.active && !progress.message.is_empty() {
-                app.step = AppStep::Complete;
-                app.status = progress.message.clone();
+        let finished = match app.download_progress.lock() {
+            Ok(progress) if !progress.active && !progress.message.is_empty() => {
+                Some((progress.message.clone(), progress.outcome, progress.files.clone()))
             }
+            _ => None,
+        };
+        if let Some((message, outcome, files)) = finished {
+            if let Some(job) = app.current_job() {
+                resume::mark_finished(&paths.jobs_file, &job)?;
+                if let Some(outcome) = outcome {
+                    subscriptions::record_sync(&paths.subscriptions_file, &paths.archive_dir, &job, outcome, files.len())?;
+                    // A sync that found nothing new isn't worth a history entry
+                    let empty_sync = job.archive.is_some() && files.is_empty() && outcome == Outcome::Success;
+                    if !empty_sync {
+                        history::append(&paths.history_file, HistoryEntry::from_result(&job, outcome, &files))?;
+                    }
+                }
+                app.results.push((job, outcome.unwrap_or(Outcome::Failed)));
+            }
+
+            if start_next_job(app, paths, config)? {
+                return Ok(());
+            }
+
+            app.step = AppStep::Complete;
+            app.status = if app.results.len() > 1 {
+                let succeeded = app.results.iter().filter(|(_, o)| *o == Outcome::Success).count();
+                format!("{} of {} downloads succeeded. {}", succeeded, app.results.len(), message)
+            } else {
+                message
+            };
         }
     }
+    Ok(())
 }
 
-fn update_spinner(app: &mut AppState) {
+/// Advances the spinner, returning whether there is one to redraw.
+fn update_spinner(app: &mut AppState) -> bool {
     if app.step == AppStep::Downloading {
         if let Ok(mut progress) = app.download_progress.lock() {
             if progress.active {
                 progress.spinner_index = (progress.spinner_index + 1) % 8;
+                return true;
             }
         }
     }
+    false
 }
@@ -0,0 +1,24 @@
+use std::io;
+use std::path::Path;
+use std::process::Command;
+
+/// Opens a file or folder with the system's default application.
+pub fn open_path(path: &Path) -> io::Result<()> {
+    #[cfg(target_os = "windows")]
+    let mut command = Command::new("explorer");
+    #[cfg(target_os = "macos")]
+    let mut command = Command::new("open");
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let mut command = Command::new("xdg-open");
+
+    command.arg(path).spawn()?;
+    Ok(())
+}
+
+/// Opens the folder containing the given file.
+pub fn open_containing_folder(path: &Path) -> io::Result<()> {
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => open_path(parent),
+        _ => open_path(Path::new(".")),
+    }
+}
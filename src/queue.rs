@@ -0,0 +1,88 @@
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+use crate::channel::ChannelFilters;
+use crate::job::{self, Job};
+use crate::url;
+
+/// Name of the file the queue is exported to, in the project root.
+pub const EXPORT_FILE: &str = "exported.queue.json";
+
+/// Exported queues end in this, which sets them apart from the app's own JSON files.
+pub const SUFFIX: &str = ".queue.json";
+
+/// A job as written to an exported queue, with type and format by name so it can be edited by hand.
+#[derive(Serialize, Deserialize)]
+struct ExportedJob {
+    url: String,
+    #[serde(rename = "type")]
+    download_type: String,
+    format: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    template: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    output_dir: Option<PathBuf>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    filters: Option<ChannelFilters>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct QueueFile {
+    jobs: Vec<ExportedJob>,
+}
+
+/// Writes the jobs so they can be imported on another machine.
+///
+/// Download archives are left out; they belong to this machine's subscriptions.
+pub fn export(path: &Path, jobs: &[Job]) -> Result<(), Box<dyn Error>> {
+    let jobs = jobs
+        .iter()
+        .map(|job| ExportedJob {
+            url: job.url.clone(),
+            download_type: job::type_name(job.download_type).to_lowercase(),
+            format: job::format_name(job.download_type, job.format).to_lowercase(),
+            template: job.template.clone(),
+            output_dir: job.output_dir.clone(),
+            filters: job.filters.clone(),
+        })
+        .collect();
+    std::fs::write(path, serde_json::to_string_pretty(&QueueFile { jobs })?)?;
+    Ok(())
+}
+
+/// Reads an exported queue, checking every job; if any is invalid, all problems are returned instead.
+pub fn load(path: &Path) -> Result<Vec<Job>, Vec<String>> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| vec![format!("Could not read {}: {}", path.display(), e)])?;
+    let file: QueueFile = serde_json::from_str(&contents)
+        .map_err(|e| vec![format!("{} is not an exported queue: {}", path.display(), e)])?;
+
+    let mut jobs = Vec::new();
+    let mut errors = Vec::new();
+    for (i, exported) in file.jobs.into_iter().enumerate() {
+        match to_job(exported) {
+            Ok(job) => jobs.push(job),
+            Err(e) => errors.push(format!("Job {}: {}", i + 1, e)),
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+    if jobs.is_empty() {
+        return Err(vec![format!("{} contains no jobs", path.display())]);
+    }
+    Ok(jobs)
+}
+
+fn to_job(exported: ExportedJob) -> Result<Job, String> {
+    let (download_type, format) = job::parse_settings(&exported.download_type, Some(&exported.format))?;
+    let url = url::parse(&exported.url)?.normalized();
+    Ok(Job {
+        template: exported.template,
+        output_dir: exported.output_dir,
+        filters: exported.filters,
+        ..Job::new(url, download_type, format)
+    })
+}
@@ -0,0 +1,76 @@
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+use crate::job::{self, Job};
+
+/// Extensions yt-dlp uses for files it hasn't finished writing.
+const PARTIAL_SUFFIXES: [&str; 3] = [".part", ".ytdl", ".temp"];
+
+/// A job that was started but never reported completion.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct InterruptedJob {
+    pub job: Job,
+    pub started_at: u64,
+}
+
+pub fn load(path: &Path) -> Vec<InterruptedJob> {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+fn save(path: &Path, jobs: &[InterruptedJob]) -> Result<(), Box<dyn Error>> {
+    if jobs.is_empty() {
+        if path.exists() {
+            std::fs::remove_file(path)?;
+        }
+        return Ok(());
+    }
+    std::fs::write(path, serde_json::to_string_pretty(jobs)?)?;
+    Ok(())
+}
+
+/// Records a job as in progress until `mark_finished` is called for it.
+pub fn mark_started(path: &Path, job: &Job) -> Result<(), Box<dyn Error>> {
+    let mut jobs = load(path);
+    jobs.retain(|j| &j.job != job);
+    jobs.push(InterruptedJob {
+        job: job.clone(),
+        started_at: job::unix_time(),
+    });
+    save(path, &jobs)
+}
+
+pub fn mark_finished(path: &Path, job: &Job) -> Result<(), Box<dyn Error>> {
+    let mut jobs = load(path);
+    jobs.retain(|j| &j.job != job);
+    save(path, &jobs)
+}
+
+/// Deletes partial download files in the output folder written since the job started.
+pub fn remove_partial_files(output_dir: &Path, started_at: u64) -> Result<usize, Box<dyn Error>> {
+    let mut removed = 0;
+    for entry in std::fs::read_dir(output_dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        let is_partial = PARTIAL_SUFFIXES.iter().any(|s| name.ends_with(s))
+            || name.contains(".part-Frag");
+        if !is_partial {
+            continue;
+        }
+
+        let modified = entry
+            .metadata()?
+            .modified()?
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        if modified >= started_at {
+            std::fs::remove_file(entry.path())?;
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
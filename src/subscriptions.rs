@@ -0,0 +1,164 @@
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use crate::channel::ChannelFilters;
+use crate::history::Outcome;
+use crate::job::{self, Job};
+use crate::ui;
+use crate::url::{self, UrlKind};
+
+/// A channel or playlist whose new videos are fetched on every sync.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Subscription {
+    pub name: String,
+    pub url: String,
+    pub download_type: usize,
+    pub format: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filters: Option<ChannelFilters>,
+    #[serde(default)]
+    pub last_sync: Option<u64>,
+    /// What the last sync did, e.g. "3 new videos".
+    #[serde(default)]
+    pub last_result: Option<String>,
+}
+
+impl Subscription {
+    pub fn new(name: String, job: &Job) -> Self {
+        Subscription {
+            name,
+            url: job.url.clone(),
+            download_type: job.download_type,
+            format: job.format,
+            template: job.template.clone(),
+            filters: job.filters.clone(),
+            last_sync: None,
+            last_result: None,
+        }
+    }
+
+    /// The job that fetches everything not yet in this subscription's archive.
+    pub fn job(&self, archive_dir: &Path) -> Job {
+        Job {
+            filters: self.filters.clone(),
+            template: self.template.clone(),
+            archive: Some(self.archive_file(archive_dir)),
+            ..Job::new(self.url.clone(), self.download_type, self.format)
+        }
+    }
+
+    /// One archive per URL, type and format, so the same video can be kept as both audio and video.
+    fn archive_file(&self, archive_dir: &Path) -> PathBuf {
+        let key = format!("{}|{}|{}", self.url, self.download_type, self.format);
+        // FNV-1a, stable across runs and Rust versions unlike the std hasher
+        let hash = key
+            .bytes()
+            .fold(0xcbf2_9ce4_8422_2325u64, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3));
+        archive_dir.join(format!("{:016x}.txt", hash))
+    }
+
+    pub fn describe(&self) -> String {
+        format!(
+            "{}/{}",
+            job::type_name(self.download_type),
+            job::format_name(self.download_type, self.format)
+        )
+    }
+}
+
+/// A readable name for a channel or playlist URL, e.g. `@name/videos`.
+pub fn default_name(url: &str) -> String {
+    match url::parse(url).map(|u| u.kind) {
+        Ok(UrlKind::Channel { path }) => path,
+        Ok(UrlKind::Playlist { list }) | Ok(UrlKind::VideoInPlaylist { list, .. }) => format!("playlist {}", list),
+        _ => url.to_string(),
+    }
+}
+
+pub fn last_sync_text(subscription: &Subscription) -> String {
+    match (subscription.last_sync, &subscription.last_result) {
+        (Some(time), Some(result)) => format!("last sync {}: {}", ui::format_timestamp(time), result),
+        (Some(time), None) => format!("last sync {}", ui::format_timestamp(time)),
+        _ => "never synced".to_string(),
+    }
+}
+
+pub fn load(path: &Path) -> Vec<Subscription> {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+pub fn save(path: &Path, subscriptions: &[Subscription]) -> Result<(), Box<dyn Error>> {
+    std::fs::write(path, serde_json::to_string_pretty(subscriptions)?)?;
+    Ok(())
+}
+
+/// Adds a subscription and returns it; a clashing name gets a number appended.
+pub fn add(path: &Path, name: &str, job: &Job) -> Result<Subscription, Box<dyn Error>> {
+    let mut subscriptions = load(path);
+    if let Some(existing) = subscriptions
+        .iter()
+        .find(|s| s.url == job.url && s.download_type == job.download_type && s.format == job.format)
+    {
+        return Err(format!("Already subscribed as '{}'", existing.name).into());
+    }
+
+    let mut unique = name.to_string();
+    let mut n = 2;
+    while subscriptions.iter().any(|s| s.name == unique) {
+        unique = format!("{} ({})", name, n);
+        n += 1;
+    }
+    let subscription = Subscription::new(unique, job);
+    subscriptions.push(subscription.clone());
+    save(path, &subscriptions)?;
+    Ok(subscription)
+}
+
+pub fn remove(path: &Path, name: &str) -> Result<bool, Box<dyn Error>> {
+    let mut subscriptions = load(path);
+    let before = subscriptions.len();
+    subscriptions.retain(|s| s.name != name);
+    if subscriptions.len() == before {
+        return Ok(false);
+    }
+    save(path, &subscriptions)?;
+    Ok(true)
+}
+
+/// Stores the outcome of syncing the subscription that ran `job`, if any did.
+pub fn record_sync(
+    path: &Path,
+    archive_dir: &Path,
+    job: &Job,
+    outcome: Outcome,
+    new_files: usize,
+) -> Result<(), Box<dyn Error>> {
+    if job.archive.is_none() {
+        return Ok(());
+    }
+    let mut subscriptions = load(path);
+    let Some(subscription) = subscriptions
+        .iter_mut()
+        .find(|s| Some(s.archive_file(archive_dir)) == job.archive)
+    else {
+        return Ok(());
+    };
+
+    subscription.last_sync = Some(job::unix_time());
+    subscription.last_result = Some(sync_summary(outcome, new_files));
+    save(path, &subscriptions)
+}
+
+pub fn sync_summary(outcome: Outcome, new_files: usize) -> String {
+    match outcome {
+        Outcome::Success | Outcome::NoSubtitles if new_files == 0 => "nothing new".to_string(),
+        Outcome::Success | Outcome::NoSubtitles => format!("{} new", new_files),
+        Outcome::Failed => format!("failed after {} new", new_files),
+        Outcome::Cancelled => format!("cancelled after {} new", new_files),
+    }
+}
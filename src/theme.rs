@@ -0,0 +1,160 @@
+use ratatui::style::{Color, Modifier, Style};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use crate::config::Config;
+
+/// Names of the themes that don't need to be defined in the config file.
+pub const BUILTIN_THEMES: [&str; 3] = ["dark", "light", "high-contrast"];
+
+/// Styles for each kind of element the UI draws.
+#[derive(Clone, Copy, Debug)]
+pub struct Theme {
+    /// Titles, the current step and status text.
+    pub accent: Style,
+    /// Chosen values such as the download type and format.
+    pub value: Style,
+    /// URLs, key hints and other things to look at.
+    pub highlight: Style,
+    /// Labels, separators and secondary details.
+    pub muted: Style,
+    pub success: Style,
+    pub warning: Style,
+    pub error: Style,
+    /// The selected row in lists.
+    pub selection: Style,
+}
+
+/// A theme from the config file; unset colors are taken from `base`.
+#[derive(Deserialize, Default, Clone)]
+#[serde(default)]
+pub struct CustomTheme {
+    pub base: Option<String>,
+    pub accent: Option<String>,
+    pub value: Option<String>,
+    pub highlight: Option<String>,
+    pub muted: Option<String>,
+    pub success: Option<String>,
+    pub warning: Option<String>,
+    pub error: Option<String>,
+    /// Background color of the selected row.
+    pub selection: Option<String>,
+}
+
+impl Theme {
+    pub fn dark() -> Self {
+        Theme {
+            accent: fg(Color::Cyan),
+            value: fg(Color::Green),
+            highlight: fg(Color::Yellow),
+            muted: fg(Color::DarkGray),
+            success: fg(Color::Green),
+            warning: fg(Color::Yellow),
+            error: fg(Color::Red),
+            selection: Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD),
+        }
+    }
+
+    pub fn light() -> Self {
+        Theme {
+            accent: fg(Color::Blue),
+            value: fg(Color::Green),
+            highlight: fg(Color::Magenta),
+            muted: fg(Color::Gray),
+            success: fg(Color::Green),
+            warning: fg(Color::Rgb(175, 95, 0)),
+            error: fg(Color::Red),
+            selection: Style::default().bg(Color::LightBlue).add_modifier(Modifier::BOLD),
+        }
+    }
+
+    pub fn high_contrast() -> Self {
+        Theme {
+            accent: fg(Color::White).add_modifier(Modifier::BOLD),
+            value: fg(Color::LightGreen).add_modifier(Modifier::BOLD),
+            highlight: fg(Color::LightYellow).add_modifier(Modifier::BOLD),
+            muted: fg(Color::White),
+            success: fg(Color::LightGreen).add_modifier(Modifier::BOLD),
+            warning: fg(Color::LightYellow).add_modifier(Modifier::BOLD),
+            error: fg(Color::LightRed).add_modifier(Modifier::BOLD),
+            selection: Style::default().fg(Color::Black).bg(Color::LightYellow).add_modifier(Modifier::BOLD),
+        }
+    }
+
+    /// Text attributes only, for terminals or users that don't want color.
+    pub fn no_color() -> Self {
+        Theme {
+            accent: modifier(Modifier::BOLD),
+            value: Style::default(),
+            highlight: modifier(Modifier::UNDERLINED),
+            muted: modifier(Modifier::DIM),
+            success: modifier(Modifier::BOLD),
+            warning: modifier(Modifier::BOLD),
+            error: modifier(Modifier::BOLD | Modifier::UNDERLINED),
+            selection: modifier(Modifier::REVERSED | Modifier::BOLD),
+        }
+    }
+
+    /// Picks the theme named in the config, unless `NO_COLOR` is set.
+    pub fn from_config(config: &Config) -> Result<Self, String> {
+        if std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty()) {
+            return Ok(Theme::no_color());
+        }
+        let name = config.theme.as_deref().unwrap_or("dark");
+        resolve(name, &config.themes, 0)
+    }
+}
+
+fn resolve(name: &str, custom: &HashMap<String, CustomTheme>, depth: usize) -> Result<Theme, String> {
+    match name {
+        "dark" => return Ok(Theme::dark()),
+        "light" => return Ok(Theme::light()),
+        "high-contrast" => return Ok(Theme::high_contrast()),
+        "none" => return Ok(Theme::no_color()),
+        _ => {}
+    }
+    let Some(theme) = custom.get(name) else {
+        return Err(format!(
+            "Unknown theme '{}' (built-in themes: {}, none)",
+            name,
+            BUILTIN_THEMES.join(", ")
+        ));
+    };
+    if depth > custom.len() {
+        return Err(format!("Theme '{}' has a circular base", name));
+    }
+
+    let mut resolved = resolve(theme.base.as_deref().unwrap_or("dark"), custom, depth + 1)?;
+    let fields = [
+        (&theme.accent, &mut resolved.accent),
+        (&theme.value, &mut resolved.value),
+        (&theme.highlight, &mut resolved.highlight),
+        (&theme.muted, &mut resolved.muted),
+        (&theme.success, &mut resolved.success),
+        (&theme.warning, &mut resolved.warning),
+        (&theme.error, &mut resolved.error),
+    ];
+    for (color, style) in fields {
+        if let Some(color) = color {
+            *style = style.fg(parse_color(name, color)?);
+        }
+    }
+    if let Some(color) = &theme.selection {
+        resolved.selection = resolved.selection.bg(parse_color(name, color)?);
+    }
+    Ok(resolved)
+}
+
+/// Accepts color names (`red`, `lightblue`), `#rrggbb` and 256-color indices.
+fn parse_color(theme: &str, color: &str) -> Result<Color, String> {
+    Color::from_str(color).map_err(|_| format!("Theme '{}': invalid color '{}'", theme, color))
+}
+
+fn fg(color: Color) -> Style {
+    Style::default().fg(color)
+}
+
+fn modifier(modifier: Modifier) -> Style {
+    Style::default().add_modifier(modifier)
+}
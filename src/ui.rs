@@ -1,38 +1,282 @@
 use ratatui::{
-    layout::{Constraint, Direction, Layout, Alignment},
-    style::{Color, Modifier, Style},
+    layout::{Constraint, Direction, Layout, Alignment, Rect},
+    style::{Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Paragraph, List, ListItem},
+    widgets::{Block, Borders, Clear, Paragraph, List, ListItem, Wrap},
     Frame,
 };
-use crate::app::{AppState, AppStep};
+use crate::app::{AppState, AppStep, ClickAreas};
+use crate::channel::{self, Preview};
+use crate::display;
+use crate::history::{HistoryEntry, Outcome};
+use crate::job;
+use crate::keymap::Action;
+use crate::subscriptions;
+use crate::theme::Theme;
 
-pub fn render_ui(f: &mut Frame, app: &AppState) {
+/// Below this size the UI can't be drawn meaningfully.
+const MIN_WIDTH: u16 = 44;
+const MIN_HEIGHT: u16 = 14;
+/// Shorter terminals get a one-line summary instead of the information panel.
+const COMPACT_HEIGHT: u16 = 30;
+/// Wider terminals show the queue and log next to the main content.
+const WIDE_WIDTH: u16 = 120;
+
+/// Draws the whole UI and returns where clickable elements ended up.
+pub fn render_ui(f: &mut Frame, app: &AppState) -> ClickAreas {
+    let mut areas = ClickAreas::default();
+    let size = f.size();
+    if size.width < MIN_WIDTH || size.height < MIN_HEIGHT {
+        render_too_small(f, app, size);
+        return areas;
+    }
+
+    let compact = size.height < COMPACT_HEIGHT;
+    let (margin, info_height) = if compact { (0, 1) } else { (2, 10) };
     let chunks = Layout::default()
         .direction(Direction::Vertical)
-        .margin(2)
+        .vertical_margin(margin)
+        .horizontal_margin(margin.max(1))
         .constraints([
             Constraint::Length(3),
-            Constraint::Length(10),
+            Constraint::Length(info_height),
             Constraint::Min(5),
             Constraint::Length(3),
         ])
-        .split(f.size());
+        .split(size);
 
-    render_title(f, chunks[0]);
-    render_info(f, app, chunks[1]);
-    render_main_content(f, app, chunks[2]);
+    render_title(f, app, chunks[0]);
+    if compact {
+        render_info_summary(f, app, chunks[1]);
+    } else {
+        render_info(f, app, chunks[1]);
+    }
+
+    if size.width >= WIDE_WIDTH {
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+            .split(chunks[2]);
+        render_main_content(f, app, columns[0], &mut areas);
+        render_side_panel(f, app, columns[1], &mut areas);
+    } else {
+        render_main_content(f, app, chunks[2], &mut areas);
+    }
     render_help(f, app, chunks[3]);
+
+    if app.show_help {
+        render_help_overlay(f, app);
+    }
+    if app.confirm_quit {
+        render_quit_dialog(f, app);
+    }
+    areas
 }
 
-fn render_title(f: &mut Frame, area: ratatui::layout::Rect) {
-    let title = Paragraph::new("YouTube Downloader")
-        .style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))
+fn render_too_small(f: &mut Frame, app: &AppState, area: Rect) {
+    let text = vec![
+        Line::from(Span::styled(
+            "Terminal too small",
+            app.theme.highlight.add_modifier(Modifier::BOLD),
+        )),
+        Line::from(format!("{}x{} (need {}x{})", area.width, area.height, MIN_WIDTH, MIN_HEIGHT)),
+    ];
+    let notice = Paragraph::new(text)
         .alignment(Alignment::Center)
-        .block(Block::default().borders(Borders::ALL));
+        .wrap(Wrap { trim: true });
+    let height = 2.min(area.height);
+    f.render_widget(notice, Rect { y: area.y + (area.height - height) / 2, height, ..area });
+}
+
+/// Queue and log shown beside the main content on wide terminals.
+fn render_side_panel(f: &mut Frame, app: &AppState, area: Rect, areas: &mut ClickAreas) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+        .split(area);
+
+    render_queue(f, app, chunks[0]);
+    render_log(f, app, chunks[1]);
+    areas.log = Some(chunks[1]);
+}
+
+fn render_queue(f: &mut Frame, app: &AppState, area: Rect) {
+    let width = (area.width as usize).saturating_sub(4);
+    let item = |mark: &'static str, style: Style, url: &str| {
+        ListItem::new(Line::from(vec![
+            Span::styled(mark, style),
+            Span::raw(display::truncate_middle(url, width)),
+        ]))
+    };
+
+    let mut items: Vec<ListItem> = app
+        .results
+        .iter()
+        .map(|(job, outcome)| match outcome {
+            Outcome::Success => item("✓ ", app.theme.success, &job.url),
+            Outcome::NoSubtitles => item("- ", app.theme.warning, &job.url),
+            Outcome::Failed => item("✗ ", app.theme.error, &job.url),
+            Outcome::Cancelled => item("✗ ", app.theme.muted, &job.url),
+        })
+        .collect();
+    if app.is_downloading() {
+        items.push(item("▶ ", app.theme.accent, &app.url));
+    }
+    items.extend(app.queue.iter().map(|job| item("· ", app.theme.muted, &job.url)));
+    items.extend(app.queued_urls.iter().map(|url| item("· ", app.theme.muted, url)));
+
+    let title = format!("Queue ({})", items.len());
+    let queue = if items.is_empty() {
+        List::new(vec![ListItem::new(Span::styled("No jobs yet", app.theme.muted))])
+    } else {
+        List::new(items)
+    };
+    f.render_widget(queue.block(Block::default().borders(Borders::ALL).title(title)), area);
+}
+
+fn render_log(f: &mut Frame, app: &AppState, area: Rect) {
+    let visible_lines = (area.height as usize).saturating_sub(2);
+    let width = (area.width as usize).saturating_sub(2);
+    let lines: Vec<Line> = match app.download_progress.lock() {
+        Ok(progress) => {
+            let end = progress.log.len().saturating_sub(app.log_scroll);
+            let start = end.saturating_sub(visible_lines);
+            progress.log[start..end]
+                .iter()
+                .map(|line| Line::from(display::truncate_end(line, width)))
+                .collect()
+        }
+        Err(_) => Vec::new(),
+    };
+
+    let title = if app.log_scroll > 0 {
+        format!("Log (scrolled back {} lines)", app.log_scroll)
+    } else {
+        "Log".to_string()
+    };
+    let log = Paragraph::new(lines)
+        .style(app.theme.muted)
+        .block(Block::default().borders(Borders::ALL).title(title));
+    f.render_widget(log, area);
+}
+
+const BREADCRUMB_STEPS: [&str; 5] = ["Type", "URL", "Format", "Confirm", "Download"];
+
+fn render_title(f: &mut Frame, app: &AppState, area: ratatui::layout::Rect) {
+    let current = match app.step {
+        AppStep::SelectType => Some(0),
+        AppStep::EnterUrl | AppStep::PlaylistChoice | AppStep::ChannelFilters => Some(1),
+        AppStep::SelectFormat => Some(2),
+        AppStep::Confirm => Some(3),
+        AppStep::Downloading | AppStep::Complete => Some(4),
+        AppStep::Resume | AppStep::History | AppStep::Subscriptions | AppStep::BatchImport => None,
+    };
+
+    let breadcrumb = match current {
+        Some(current) => {
+            let mut spans = Vec::new();
+            for (i, name) in BREADCRUMB_STEPS.iter().enumerate() {
+                if i > 0 {
+                    spans.push(Span::styled(" › ", app.theme.muted));
+                }
+                let style = if i == current {
+                    app.theme.accent.add_modifier(Modifier::BOLD)
+                } else if i < current {
+                    app.theme.value
+                } else {
+                    app.theme.muted
+                };
+                spans.push(Span::styled(*name, style));
+            }
+            Line::from(spans)
+        }
+        None => {
+            let name = match app.step {
+                AppStep::History => "History",
+                AppStep::Subscriptions => "Subscriptions",
+                AppStep::BatchImport => "Import",
+                _ => "Resume",
+            };
+            Line::from(Span::styled(name, app.theme.accent.add_modifier(Modifier::BOLD)))
+        }
+    };
+
+    let title = Paragraph::new(breadcrumb)
+        .alignment(Alignment::Center)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("YouTube Downloader")
+                .title_alignment(Alignment::Center)
+                .title_style(app.theme.accent.add_modifier(Modifier::BOLD)),
+        );
     f.render_widget(title, area);
 }
 
+fn render_quit_dialog(f: &mut Frame, app: &AppState) {
+    if app.is_downloading() {
+        render_quit_while_downloading_dialog(f, app);
+        return;
+    }
+
+    let area = centered_rect(40, 7, f.size());
+    let text = vec![
+        Line::from(""),
+        Line::from("Quit YouTube Downloader?"),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("[y]", app.theme.success.add_modifier(Modifier::BOLD)),
+            Span::raw(" Quit   "),
+            Span::styled("[n]", app.theme.error.add_modifier(Modifier::BOLD)),
+            Span::raw(" Stay"),
+        ]),
+    ];
+
+    let dialog = Paragraph::new(text)
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::ALL).title("Quit"));
+    f.render_widget(Clear, area);
+    f.render_widget(dialog, area);
+}
+
+fn render_quit_while_downloading_dialog(f: &mut Frame, app: &AppState) {
+    let area = centered_rect(56, 10, f.size());
+    let key_style = app.theme.highlight.add_modifier(Modifier::BOLD);
+    let option = |key: &'static str, label: &'static str| {
+        Line::from(vec![Span::styled(key, key_style), Span::raw(label)])
+    };
+    let text = vec![
+        Line::from(""),
+        Line::from("Downloads are still running."),
+        Line::from(""),
+        option("[w] ", "Wait for them to finish, then quit"),
+        option("[c] ", "Cancel downloads and quit"),
+        option("[d] ", "Detach: keep downloading in the background"),
+        option("[n] ", "Stay"),
+    ];
+
+    let dialog = Paragraph::new(text)
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::ALL).title("Quit"));
+    f.render_widget(Clear, area);
+    f.render_widget(dialog, area);
+}
+
+/// A rectangle of the given size centered in `area`, shrunk to fit if needed.
+fn centered_rect(width: u16, height: u16, area: Rect) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}
+
+/// Width of the "  Type:   " style labels in the information panel.
+const INFO_LABEL_WIDTH: usize = 10;
+
 fn render_info(f: &mut Frame, app: &AppState, area: ratatui::layout::Rect) {
     let type_str = match app.download_type {
         Some(0) => "Video",
@@ -43,26 +287,33 @@ fn render_info(f: &mut Frame, app: &AppState, area: ratatui::layout::Rect) {
     };
 
     let format_str = get_format_string(app);
-    let url_display = get_url_display(&app.url);
+    // Room left for values after the borders and the "  Label:  " column
+    let value_width = (area.width as usize).saturating_sub(2 + INFO_LABEL_WIDTH);
+
+    let queued = app.queued_urls.len() + app.queue.len();
+    let queued_suffix = if queued > 0 { format!(" (+{} queued)", queued) } else { String::new() };
+    let url_width = value_width.saturating_sub(display::width(&queued_suffix));
+    let url_display = get_url_display(&app.url, url_width) + &queued_suffix;
+    let status = display::truncate_end(&app.status, value_width);
 
     let info_text = vec![
         Line::from(""),
         Line::from(vec![
-            Span::styled("  Type:   ", Style::default().fg(Color::DarkGray)),
-            Span::styled(type_str, Style::default().fg(Color::Green)),
+            Span::styled("  Type:   ", app.theme.muted),
+            Span::styled(type_str, app.theme.value),
         ]),
         Line::from(vec![
-            Span::styled("  Format: ", Style::default().fg(Color::DarkGray)),
-            Span::styled(format_str, Style::default().fg(Color::Green)),
+            Span::styled("  Format: ", app.theme.muted),
+            Span::styled(format_str, app.theme.value),
         ]),
         Line::from(vec![
-            Span::styled("  URL:    ", Style::default().fg(Color::DarkGray)),
-            Span::styled(url_display, Style::default().fg(Color::Yellow)),
+            Span::styled("  URL:    ", app.theme.muted),
+            Span::styled(url_display, app.theme.highlight),
         ]),
         Line::from(""),
         Line::from(vec![
-            Span::styled("  Status: ", Style::default().fg(Color::DarkGray)),
-            Span::styled(&app.status, Style::default().fg(Color::Cyan)),
+            Span::styled("  Status: ", app.theme.muted),
+            Span::styled(status, app.theme.accent),
         ]),
     ];
 
@@ -71,71 +322,214 @@ fn render_info(f: &mut Frame, app: &AppState, area: ratatui::layout::Rect) {
     f.render_widget(info, area);
 }
 
+fn render_info_summary(f: &mut Frame, app: &AppState, area: Rect) {
+    let type_str = app.download_type.map(job::type_name).unwrap_or("No type");
+    let format_str = get_format_string(app);
+    let queued = app.queued_urls.len() + app.queue.len();
+    let queued_suffix = if queued > 0 { format!(" (+{})", queued) } else { String::new() };
+
+    let head = format!(" {} · {} · ", type_str, format_str);
+    let available = (area.width as usize).saturating_sub(display::width(&head));
+    // Split what's left between the URL and the status, favouring the status
+    let url_width = (available / 3).saturating_sub(display::width(&queued_suffix));
+    let url = get_url_display(&app.url, url_width) + &queued_suffix;
+    let status_width = available.saturating_sub(display::width(&url) + 3);
+
+    let summary = Line::from(vec![
+        Span::styled(head, app.theme.value),
+        Span::styled(url, app.theme.highlight),
+        Span::styled(" │ ", app.theme.muted),
+        Span::styled(display::truncate_end(&app.status, status_width), app.theme.accent),
+    ]);
+    f.render_widget(Paragraph::new(summary), area);
+}
+
 fn get_format_string(app: &AppState) -> &'static str {
     if let Some(dtype) = app.download_type {
         if let Some(fmt) = app.format {
-            return match dtype {
-                0 => ["MP4", "MKV", "WebM"][fmt],
-                1 => ["FLAC", "MP3", "WAV", "AAC", "M4A"][fmt],
-                2 => ["English", "All"][fmt],
-                _ => "Unknown",
-            };
+            return job::format_name(dtype, fmt);
         }
     }
     "Not selected"
 }
 
-fn get_url_display(url: &str) -> &str {
+fn get_url_display(url: &str, max_width: usize) -> String {
     if url.is_empty() {
-        "Not entered"
-    } else if url.len() > 50 {
-        &url[..50]
+        "Not entered".to_string()
     } else {
-        url
+        display::truncate_middle(url, max_width)
     }
 }
 
-fn render_main_content(f: &mut Frame, app: &AppState, area: ratatui::layout::Rect) {
+fn render_main_content(f: &mut Frame, app: &AppState, area: ratatui::layout::Rect, areas: &mut ClickAreas) {
     match app.step {
-        AppStep::SelectType => render_select_type(f, app, area),
+        AppStep::Resume => render_resume(f, app, area, areas),
+        AppStep::SelectType => render_select_type(f, app, area, areas),
         AppStep::EnterUrl => render_url_input(f, app, area),
-        AppStep::SelectFormat => render_select_format(f, app, area),
-        AppStep::Confirm => render_confirm(f, app, area),
+        AppStep::PlaylistChoice => render_playlist_choice(f, app, area, areas),
+        AppStep::ChannelFilters => render_channel_filters(f, app, area, areas),
+        AppStep::SelectFormat => render_select_format(f, app, area, areas),
+        AppStep::Confirm => render_confirm(f, app, area, areas),
         AppStep::Downloading => render_downloading(f, app, area),
-        AppStep::Complete => render_complete(f, app, area),
+        AppStep::Complete => render_complete(f, app, area, areas),
+        AppStep::History => render_history(f, app, area, areas),
+        AppStep::Subscriptions => render_subscriptions(f, app, area, areas),
+        AppStep::BatchImport => render_batch_import(f, app, area, areas),
     }
 }
 
-fn render_select_type(f: &mut Frame, app: &AppState, area: ratatui::layout::Rect) {
-    let items: Vec<ListItem> = vec!["Video", "Audio", "Subtitles"]
+/// Draws a selectable list and records where its rows are for mouse clicks.
+fn render_list(
+    f: &mut Frame,
+    app: &AppState,
+    area: Rect,
+    title: &str,
+    items: Vec<ListItem>,
+    areas: &mut ClickAreas,
+) {
+    let block = Block::default().borders(Borders::ALL).title(title.to_string());
+    let inner = block.inner(area);
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(app.theme.selection)
+        .highlight_symbol("> ");
+
+    let mut state = app.list_state.clone();
+    f.render_stateful_widget(list, area, &mut state);
+    areas.list = Some((inner, state.offset()));
+}
+
+fn render_resume(f: &mut Frame, app: &AppState, area: ratatui::layout::Rect, areas: &mut ClickAreas) {
+    let items = vec![
+        ListItem::new("Resume download"),
+        ListItem::new("Discard partial files"),
+        ListItem::new("Decide later"),
+    ];
+    render_list(f, app, area, "Interrupted Download", items, areas);
+}
+
+fn render_select_type(f: &mut Frame, app: &AppState, area: ratatui::layout::Rect, areas: &mut ClickAreas) {
+    let items: Vec<ListItem> = ["Video", "Audio", "Subtitles"]
         .iter()
         .map(|s| ListItem::new(*s))
         .collect();
-    
-    let list = List::new(items)
-        .block(Block::default().borders(Borders::ALL).title("Select Download Type"))
-        .highlight_style(Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD))
-        .highlight_symbol("> ");
-    
-    f.render_stateful_widget(list, area, &mut app.list_state.clone());
+    render_list(f, app, area, "Select Download Type", items, areas);
 }
 
 fn render_url_input(f: &mut Frame, app: &AppState, area: ratatui::layout::Rect) {
-    let input_text = if app.url.is_empty() {
-        vec![Line::from("Type your YouTube URL and press Enter...")]
-    } else {
-        vec![
+    let block = Block::default().borders(Borders::ALL).title("Enter URL");
+    let inner = block.inner(area);
+
+    if app.url_input.is_empty() {
+        let placeholder = Paragraph::new(vec![
             Line::from(""),
-            Line::from(Span::styled(&app.url, Style::default().fg(Color::Yellow))),
-        ]
-    };
-    
-    let paragraph = Paragraph::new(input_text)
-        .block(Block::default().borders(Borders::ALL).title("Enter URL"));
+            Line::from(Span::styled(
+                "Type your YouTube URL and press Enter...",
+                app.theme.muted,
+            )),
+        ])
+        .block(block);
+        f.render_widget(placeholder, area);
+        f.set_cursor(inner.x, inner.y + 1);
+        return;
+    }
+
+    let (visible, cursor_x) = app.url_input.visible(inner.width as usize);
+    let mut lines = vec![
+        Line::from(""),
+        Line::from(Span::styled(visible, app.theme.highlight)),
+    ];
+    if let Some(error) = &app.url_error {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            format!("✗ {}", error),
+            app.theme.error,
+        )));
+    }
+    let paragraph = Paragraph::new(lines).block(block);
     f.render_widget(paragraph, area);
+    f.set_cursor(inner.x + cursor_x as u16, inner.y + 1);
+}
+
+fn render_playlist_choice(f: &mut Frame, app: &AppState, area: ratatui::layout::Rect, areas: &mut ClickAreas) {
+    let items = vec![
+        ListItem::new("Just this video"),
+        ListItem::new("The whole playlist"),
+    ];
+    render_list(f, app, area, "Video in Playlist", items, areas);
 }
 
-fn render_select_format(f: &mut Frame, app: &AppState, area: ratatui::layout::Rect) {
+/// Width of the labels in the channel filter list.
+const FILTER_LABEL_WIDTH: usize = 17;
+
+fn render_channel_filters(f: &mut Frame, app: &AppState, area: ratatui::layout::Rect, areas: &mut ClickAreas) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(5), Constraint::Length(3)])
+        .split(area);
+    let selected = app.list_state.selected();
+    let editing = app.input_mode.then_some(selected).flatten();
+    // Borders plus the "> " highlight symbol and the label column
+    let value_width = (chunks[0].width as usize).saturating_sub(4 + FILTER_LABEL_WIDTH);
+    let row = |label: &str, value: Span<'static>| {
+        ListItem::new(Line::from(vec![
+            Span::raw(format!("{:<width$}", label, width = FILTER_LABEL_WIDTH)),
+            value,
+        ]))
+    };
+
+    let tab = app.parsed_url.as_ref().and_then(|p| p.channel_tab()).unwrap_or("all tabs");
+    let mut items = vec![row("Tab", Span::styled(tab.to_string(), app.theme.value))];
+    let mut cursor_x = 0;
+    for (i, field) in channel::FIELDS.iter().enumerate() {
+        let value = if editing == Some(i + 1) {
+            let (visible, x) = app.filter_input.visible(value_width);
+            cursor_x = x;
+            Span::styled(visible, app.theme.highlight)
+        } else {
+            match field.value(&app.channel_filters) {
+                value if value.is_empty() => Span::styled("any", app.theme.muted),
+                value => Span::styled(display::truncate_end(&value, value_width), app.theme.value),
+            }
+        };
+        items.push(row(field.label(), value));
+    }
+    items.push(ListItem::new("Preview matching videos"));
+    items.push(ListItem::new("Continue"));
+    render_list(f, app, chunks[0], "Channel Filters", items, areas);
+
+    if let (Some(row), Some((inner, offset))) = (editing, areas.list) {
+        if row >= offset && row - offset < inner.height as usize {
+            let x = inner.x + (2 + FILTER_LABEL_WIDTH + cursor_x) as u16;
+            f.set_cursor(x, inner.y + (row - offset) as u16);
+        }
+    }
+
+    let preview = app.channel_preview.lock().map(|p| p.clone()).unwrap_or(Preview::NotRun);
+    let dated = app.channel_filters.date_after.is_some() || app.channel_filters.date_before.is_some();
+    let line = if let Some(error) = &app.filter_error {
+        Span::styled(format!("✗ {}", error), app.theme.error)
+    } else if let Some(field) = editing.and_then(|row| channel::FIELDS.get(row.wrapping_sub(1))) {
+        Span::styled(format!("{}: {} (empty for any)", field.label(), field.hint()), app.theme.muted)
+    } else {
+        match preview {
+            Preview::NotRun => Span::styled("Select \"Preview\" to count the matching videos", app.theme.muted),
+            Preview::Running => Span::styled("Counting matching videos...", app.theme.accent),
+            Preview::Matches(count) if dated => Span::styled(
+                format!("{} videos match (the date range is applied while downloading)", count),
+                app.theme.success,
+            ),
+            Preview::Matches(count) => Span::styled(format!("{} videos match", count), app.theme.success),
+            Preview::Failed(e) => Span::styled(format!("Preview failed: {}", e), app.theme.error),
+        }
+    };
+    let width = (chunks[1].width as usize).saturating_sub(2);
+    let line = Span::styled(display::truncate_end(&line.content, width), line.style);
+    let status = Paragraph::new(Line::from(line)).block(Block::default().borders(Borders::ALL));
+    f.render_widget(status, chunks[1]);
+}
+
+fn render_select_format(f: &mut Frame, app: &AppState, area: ratatui::layout::Rect, areas: &mut ClickAreas) {
     let formats = match app.download_type {
         Some(0) => vec!["MP4", "MKV", "WebM"],
         Some(1) => vec!["FLAC", "MP3", "WAV", "AAC", "M4A"],
@@ -144,27 +538,18 @@ fn render_select_format(f: &mut Frame, app: &AppState, area: ratatui::layout::Re
     };
 
     let items: Vec<ListItem> = formats.iter().map(|s| ListItem::new(*s)).collect();
-    
-    let list = List::new(items)
-        .block(Block::default().borders(Borders::ALL).title("Select Format"))
-        .highlight_style(Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD))
-        .highlight_symbol("> ");
-    
-    f.render_stateful_widget(list, area, &mut app.list_state.clone());
+    render_list(f, app, area, "Select Format", items, areas);
 }
 
-fn render_confirm(f: &mut Frame, app: &AppState, area: ratatui::layout::Rect) {
-    let items = vec![
+fn render_confirm(f: &mut Frame, app: &AppState, area: ratatui::layout::Rect, areas: &mut ClickAreas) {
+    let mut items = vec![
         ListItem::new("Start Download"),
         ListItem::new("Cancel"),
     ];
-    
-    let list = List::new(items)
-        .block(Block::default().borders(Borders::ALL).title("Confirm"))
-        .highlight_style(Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD))
-        .highlight_symbol("> ");
-    
-    f.render_stateful_widget(list, area, &mut app.list_state.clone());
+    if app.can_subscribe() {
+        items.push(ListItem::new("Subscribe (sync new videos later)"));
+    }
+    render_list(f, app, area, "Confirm", items, areas);
 }
 
 fn render_downloading(f: &mut Frame, app: &AppState, area: ratatui::layout::Rect) {
@@ -178,17 +563,18 @@ fn render_downloading(f: &mut Frame, app: &AppState, area: ratatui::layout::Rect
         };
         (spinner.to_string(), progress.message.clone())
     };
+    let message = display::truncate_end(&message, (area.width as usize).saturating_sub(4));
     
     let text = vec![
         Line::from(""),
         Line::from(vec![
             Span::styled(
                 format!("{} ", spinner),
-                Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+                app.theme.accent.add_modifier(Modifier::BOLD),
             ),
             Span::styled(
                 message,
-                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+                app.theme.highlight.add_modifier(Modifier::BOLD),
             ),
         ]),
         Line::from(""),
@@ -196,7 +582,7 @@ fn render_downloading(f: &mut Frame, app: &AppState, area: ratatui::layout::Rect
         Line::from(""),
         Line::from(Span::styled(
             "Please wait...",
-            Style::default().fg(Color::DarkGray),
+            app.theme.muted,
         )),
     ];
     
@@ -206,39 +592,392 @@ fn render_downloading(f: &mut Frame, app: &AppState, area: ratatui::layout::Rect
     f.render_widget(paragraph, area);
 }
 
-fn render_complete(f: &mut Frame, app: &AppState, area: ratatui::layout::Rect) {
-    let success = app.status.to_lowercase().contains("complete") || 
-                 app.status.to_lowercase().contains("success");
-    let color = if success { Color::Green } else { Color::Red };
+fn render_complete(f: &mut Frame, app: &AppState, area: ratatui::layout::Rect, areas: &mut ClickAreas) {
+    let success = !app.results.is_empty() && app.results.iter().all(|(_, o)| *o == Outcome::Success);
+    let style = if success { app.theme.success } else { app.theme.error };
     let symbol = if success { "[SUCCESS]" } else { "[FAILED]" };
     
     let text = vec![
         Line::from(""),
         Line::from(Span::styled(
             symbol,
-            Style::default().fg(color).add_modifier(Modifier::BOLD),
+            style.add_modifier(Modifier::BOLD),
         )),
         Line::from(""),
         Line::from(app.status.as_str()),
     ];
     
+    let block = Block::default().borders(Borders::ALL).title("Complete");
+    let inner = block.inner(area);
     let paragraph = Paragraph::new(text)
         .alignment(Alignment::Center)
-        .block(Block::default().borders(Borders::ALL).title("Complete"));
+        .wrap(Wrap { trim: true })
+        .block(block);
     f.render_widget(paragraph, area);
+
+    if inner.height > 5 {
+        let buttons_area = Rect { y: inner.y + inner.height - 1, height: 1, ..inner };
+        render_complete_buttons(f, app, buttons_area, areas);
+    }
+}
+
+/// Clickable buttons along the bottom of the completion screen.
+fn render_complete_buttons(f: &mut Frame, app: &AppState, area: Rect, areas: &mut ClickAreas) {
+    let failed = app
+        .download_progress
+        .lock()
+        .map(|p| p.outcome == Some(Outcome::Failed))
+        .unwrap_or(false);
+    let mut buttons = Vec::new();
+    if failed {
+        buttons.push((Action::Retry, "Retry"));
+    }
+    buttons.extend([(Action::Restart, "Restart"), (Action::History, "History"), (Action::Quit, "Quit")]);
+
+    let labels: Vec<String> = buttons
+        .iter()
+        .map(|(action, label)| format!(" {} ({}) ", label, app.keymap.hint(*action)))
+        .collect();
+    const GAP: u16 = 2;
+    let total: u16 = labels.iter().map(|l| display::width(l) as u16 + GAP).sum::<u16>() - GAP;
+    if total > area.width {
+        return;
+    }
+
+    let start = area.x + (area.width - total) / 2;
+    let mut x = start;
+    let mut spans = Vec::new();
+    for ((action, _), label) in buttons.iter().zip(labels) {
+        if x > start {
+            spans.push(Span::raw(" ".repeat(GAP as usize)));
+        }
+        let width = display::width(&label) as u16;
+        areas.buttons.push((Rect { x, width, ..area }, *action));
+        spans.push(Span::styled(label, app.theme.selection));
+        x += width + GAP;
+    }
+    f.render_widget(Paragraph::new(Line::from(spans)), Rect { x: start, width: total, ..area });
+}
+
+fn render_history(f: &mut Frame, app: &AppState, area: ratatui::layout::Rect, areas: &mut ClickAreas) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(3)])
+        .split(area);
+
+    let search_style = if app.history.searching {
+        app.theme.highlight
+    } else {
+        Style::default()
+    };
+    let search = Paragraph::new(app.history.search.as_str())
+        .style(search_style)
+        .block(Block::default().borders(Borders::ALL).title(format!(
+            "Search  |  Filter: {}",
+            app.history.filter.label()
+        )));
+    f.render_widget(search, chunks[0]);
+
+    // Borders plus the "> " highlight symbol
+    let list_width = (chunks[1].width as usize).saturating_sub(4);
+    let items: Vec<ListItem> = app
+        .history
+        .visible()
+        .into_iter()
+        .map(|i| history_item(&app.history.entries[i], list_width, &app.theme))
+        .collect();
+
+    render_list(f, app, chunks[1], "History", items, areas);
+}
+
+fn render_subscriptions(f: &mut Frame, app: &AppState, area: Rect, areas: &mut ClickAreas) {
+    let width = (area.width as usize).saturating_sub(4);
+    let items: Vec<ListItem> = app
+        .subscriptions
+        .iter()
+        .map(|subscription| {
+            // One row per subscription, since mouse clicks map rows to items
+            let details = format!(
+                "  ({}, {})",
+                subscription.describe(),
+                subscriptions::last_sync_text(subscription)
+            );
+            let name = display::truncate_end(&subscription.name, width.saturating_sub(display::width(&details)));
+            ListItem::new(Line::from(vec![Span::raw(name), Span::styled(details, app.theme.muted)]))
+        })
+        .collect();
+    render_list(f, app, area, "Subscriptions", items, areas);
+}
+
+fn render_batch_import(f: &mut Frame, app: &AppState, area: Rect, areas: &mut ClickAreas) {
+    // Borders plus one row per problem, leaving room for the input and a few files
+    let errors_height = match app.batch_errors.len() {
+        0 => 0,
+        n => (n as u16 + 2).min(area.height.saturating_sub(6) / 2),
+    };
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(3), Constraint::Length(errors_height)])
+        .split(area);
+
+    let block = Block::default().borders(Borders::ALL).title("File");
+    let inner = block.inner(chunks[0]);
+    let (text, cursor_x) = if app.batch_input.is_empty() {
+        let placeholder = "Type a path, or pick a file below";
+        (Span::styled(placeholder, app.theme.muted), 0)
+    } else {
+        let (visible, cursor_x) = app.batch_input.visible(inner.width as usize);
+        (Span::styled(visible, app.theme.highlight), cursor_x)
+    };
+    f.render_widget(Paragraph::new(Line::from(text)).block(block), chunks[0]);
+    f.set_cursor(inner.x + cursor_x as u16, inner.y);
+
+    let list_width = (chunks[1].width as usize).saturating_sub(4);
+    let items: Vec<ListItem> = app
+        .batch_files
+        .iter()
+        .map(|path| {
+            let name = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+            ListItem::new(display::truncate_middle(&name, list_width))
+        })
+        .collect();
+    let title = if items.is_empty() { "No .txt or .queue.json files in the project folder" } else { "Text Files" };
+    render_list(f, app, chunks[1], title, items, areas);
+
+    if !app.batch_errors.is_empty() {
+        let errors: Vec<Line> = app
+            .batch_errors
+            .iter()
+            .map(|e| Line::from(Span::styled(format!("✗ {}", e), app.theme.error)))
+            .collect();
+        let paragraph = Paragraph::new(errors)
+            .wrap(Wrap { trim: false })
+            .block(Block::default().borders(Borders::ALL).title("Problems"));
+        f.render_widget(paragraph, chunks[2]);
+    }
+}
+
+fn history_item(entry: &HistoryEntry, width: usize, theme: &Theme) -> ListItem<'static> {
+    let (mark, style) = match entry.outcome {
+        Outcome::Success => ("[OK]     ", theme.success),
+        Outcome::NoSubtitles => ("[NO SUBS]", theme.warning),
+        Outcome::Failed => ("[FAILED] ", theme.error),
+        Outcome::Cancelled => ("[CANCEL] ", theme.muted),
+    };
+    let name = entry.title.clone().unwrap_or_else(|| entry.job.url.clone());
+
+    let mut details = format!(
+        "{}/{}",
+        job::type_name(entry.job.download_type),
+        job::format_name(entry.job.download_type, entry.job.format)
+    );
+    if let Some(size) = entry.size {
+        details.push_str(&format!(", {}", format_size(size)));
+    }
+    if let Some(duration) = entry.duration {
+        details.push_str(&format!(", {}", format_duration(duration)));
+    }
+    if entry.deleted {
+        details.push_str(", deleted");
+    }
+
+    let timestamp = format!(" {}  ", format_timestamp(entry.timestamp));
+    let details = format!("  ({})", details);
+    let fixed_width = display::width(mark) + display::width(&timestamp) + display::width(&details);
+    let name = if entry.title.is_some() {
+        display::truncate_end(&name, width.saturating_sub(fixed_width))
+    } else {
+        display::truncate_middle(&name, width.saturating_sub(fixed_width))
+    };
+
+    ListItem::new(Line::from(vec![
+        Span::styled(mark, style),
+        Span::styled(timestamp, theme.muted),
+        Span::raw(name),
+        Span::styled(details, theme.muted),
+    ]))
+}
+
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+fn format_duration(seconds: f64) -> String {
+    let total = seconds.round() as u64;
+    let (hours, minutes, secs) = (total / 3600, (total % 3600) / 60, total % 60);
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, secs)
+    } else {
+        format!("{}:{:02}", minutes, secs)
+    }
+}
+
+/// Formats a Unix timestamp as a UTC date and time.
+pub fn format_timestamp(timestamp: u64) -> String {
+    let days = (timestamp / 86_400) as i64;
+    let time = timestamp % 86_400;
+
+    // Civil-from-days conversion (proleptic Gregorian calendar)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}",
+        year, month, day, time / 3600, (time % 3600) / 60
+    )
 }
 
 fn render_help(f: &mut Frame, app: &AppState, area: ratatui::layout::Rect) {
-    let help_text = match app.step {
-        AppStep::Complete => "Press 'r' to restart  |  Press 'q' to quit",
-        AppStep::EnterUrl => "Type URL and press Enter",
-        AppStep::Downloading => "Please wait...",
-        _ => "Use Arrow Keys to navigate  |  Press Enter to select  |  Press 'q' to quit",
+    let failed = app
+        .download_progress
+        .lock()
+        .map(|p| p.outcome == Some(Outcome::Failed))
+        .unwrap_or(false);
+    let key = |action| app.keymap.hint(action);
+    let navigate = format!("{}/{} navigate", key(Action::Up), key(Action::Down));
+    let hints = match app.step {
+        AppStep::Complete if failed => vec![
+            format!("{} retry", key(Action::Retry)),
+            format!("{} export failed", key(Action::Export)),
+            format!("{} restart", key(Action::Restart)),
+            format!("{} history", key(Action::History)),
+            format!("{} quit", key(Action::Quit)),
+        ],
+        AppStep::Complete => vec![
+            format!("{} restart", key(Action::Restart)),
+            format!("{} history", key(Action::History)),
+            format!("{} quit", key(Action::Quit)),
+        ],
+        AppStep::History if app.history.searching => {
+            vec!["Type to search".to_string(), "Enter/Esc to finish".to_string()]
+        }
+        AppStep::History => vec![
+            format!("{} search", key(Action::Search)),
+            format!("{} filter", key(Action::Filter)),
+            format!("{} re-download", key(Action::Select)),
+            format!("{} open folder", key(Action::OpenFolder)),
+            format!("{} delete file", key(Action::DeleteFile)),
+            format!("{} back", key(Action::Cancel)),
+        ],
+        AppStep::SelectType => vec![
+            navigate,
+            format!("{} select", key(Action::Select)),
+            format!("{} history", key(Action::History)),
+            format!("{} subscriptions", key(Action::Subscriptions)),
+            format!("{} import", key(Action::Import)),
+            format!("{} quit", key(Action::Quit)),
+        ],
+        AppStep::BatchImport => vec![
+            "Type a path".to_string(),
+            "Up/Down pick a file".to_string(),
+            format!("{} import", key(Action::Select)),
+            format!("{} back", key(Action::Cancel)),
+        ],
+        AppStep::Subscriptions => vec![
+            navigate,
+            format!("{} sync", key(Action::Select)),
+            format!("{} sync all", key(Action::SyncAll)),
+            format!("{} unsubscribe", key(Action::DeleteFile)),
+            format!("{} back", key(Action::Cancel)),
+        ],
+        AppStep::ChannelFilters if app.input_mode => vec![
+            "Type a value".to_string(),
+            format!("{} save", key(Action::Select)),
+            format!("{} cancel", key(Action::Cancel)),
+        ],
+        AppStep::ChannelFilters => vec![
+            navigate,
+            format!("{} edit / choose", key(Action::Select)),
+            format!("{} back", key(Action::Cancel)),
+            format!("{} quit", key(Action::Quit)),
+        ],
+        AppStep::EnterUrl => vec![
+            format!("Type or paste ({}) URLs", key(Action::Paste)),
+            "Up/Down recall previous URLs".to_string(),
+            format!("{} continue", key(Action::Select)),
+            format!("{} back", key(Action::Cancel)),
+        ],
+        AppStep::Downloading if app.quit_when_done => vec![
+            "Quitting when downloads finish".to_string(),
+            format!("{} for options", key(Action::Quit)),
+        ],
+        AppStep::Downloading => vec![
+            "Please wait...".to_string(),
+            format!("{} export queue", key(Action::Export)),
+            format!("{} to quit or cancel", key(Action::Quit)),
+        ],
+        AppStep::Resume => vec![
+            navigate,
+            format!("{} select", key(Action::Select)),
+            format!("{} quit", key(Action::Quit)),
+        ],
+        _ => vec![
+            navigate,
+            format!("{} select", key(Action::Select)),
+            format!("{} back", key(Action::Back)),
+            format!("{} quit", key(Action::Quit)),
+        ],
     };
+    let mut help_text = hints.join("  |  ");
+    if !app.input_mode && !app.history.searching {
+        help_text.push_str(&format!("  |  {} all keys", key(Action::Help)));
+    }
 
     let help = Paragraph::new(help_text)
-        .style(Style::default().fg(Color::DarkGray))
+        .style(app.theme.muted)
         .alignment(Alignment::Center)
         .block(Block::default().borders(Borders::ALL));
     f.render_widget(help, area);
-}
\ No newline at end of file
+}
+
+/// Lists every action with the keys currently bound to it.
+fn render_help_overlay(f: &mut Frame, app: &AppState) {
+    let keys_width = Action::ALL
+        .iter()
+        .map(|a| display::width(&keys_label(app, *a)))
+        .max()
+        .unwrap_or(0);
+    let lines: Vec<Line> = Action::ALL
+        .iter()
+        .map(|action| {
+            let keys = keys_label(app, *action);
+            let padding = " ".repeat(keys_width - display::width(&keys));
+            Line::from(vec![
+                Span::styled(format!(" {}{}  ", keys, padding), app.theme.highlight.add_modifier(Modifier::BOLD)),
+                Span::raw(action.description()),
+            ])
+        })
+        .collect();
+
+    let area = centered_rect(64, lines.len() as u16 + 2, f.size());
+    let overlay = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("Keys (press any key to close)"),
+    );
+    f.render_widget(Clear, area);
+    f.render_widget(overlay, area);
+}
+
+fn keys_label(app: &AppState, action: Action) -> String {
+    let keys: Vec<String> = app.keymap.keys(action).iter().map(|k| k.to_string()).collect();
+    if keys.is_empty() { "-".to_string() } else { keys.join(", ") }
+}
@@ -0,0 +1,207 @@
+/// Hosts that serve YouTube pages.
+const YOUTUBE_HOSTS: [&str; 5] = [
+    "youtube.com",
+    "m.youtube.com",
+    "music.youtube.com",
+    "youtube-nocookie.com",
+    "youtu.be",
+];
+
+/// Channel pages that can follow a channel URL, e.g. `/@name/videos`.
+const CHANNEL_TABS: [&str; 6] = ["videos", "shorts", "streams", "playlists", "featured", "live"];
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum UrlKind {
+    Video { id: String },
+    Playlist { list: String },
+    /// A watch URL that also carries a playlist, e.g. `watch?v=...&list=...`.
+    VideoInPlaylist { id: String, list: String },
+    Channel { path: String },
+}
+
+/// Channel tabs offered in channel mode; the first entry (none) means the whole channel.
+pub const VIDEO_TABS: [Option<&str>; 4] = [None, Some("videos"), Some("shorts"), Some("streams")];
+
+/// A recognized YouTube URL with tracking parameters removed.
+#[derive(Clone, Debug)]
+pub struct YoutubeUrl {
+    pub kind: UrlKind,
+    music: bool,
+}
+
+impl YoutubeUrl {
+    /// The canonical URL; for a video inside a playlist, only the video.
+    pub fn normalized(&self) -> String {
+        match &self.kind {
+            UrlKind::Video { id } | UrlKind::VideoInPlaylist { id, .. } => self.video_url(id),
+            UrlKind::Playlist { list } => self.playlist_url(list),
+            UrlKind::Channel { path } => format!("https://www.youtube.com/{}", path),
+        }
+    }
+
+    /// The canonical URL of the whole playlist, if the URL has one.
+    pub fn playlist(&self) -> Option<String> {
+        match &self.kind {
+            UrlKind::Playlist { list } | UrlKind::VideoInPlaylist { list, .. } => {
+                Some(self.playlist_url(list))
+            }
+            _ => None,
+        }
+    }
+
+    /// The canonical URL of the channel, if this is one.
+    pub fn channel_url(&self) -> Option<String> {
+        match self.kind {
+            UrlKind::Channel { .. } => Some(self.normalized()),
+            _ => None,
+        }
+    }
+
+    /// The selected tab of a channel URL, e.g. `videos` for `/@name/videos`.
+    pub fn channel_tab(&self) -> Option<&str> {
+        match &self.kind {
+            UrlKind::Channel { path } => split_channel_path(path).1,
+            _ => None,
+        }
+    }
+
+    /// The same channel with a different tab, or `None` for the channel itself.
+    pub fn with_channel_tab(&self, tab: Option<&str>) -> YoutubeUrl {
+        let kind = match &self.kind {
+            UrlKind::Channel { path } => {
+                let base = split_channel_path(path).0;
+                UrlKind::Channel {
+                    path: match tab {
+                        Some(tab) => format!("{}/{}", base, tab),
+                        None => base.to_string(),
+                    },
+                }
+            }
+            kind => kind.clone(),
+        };
+        YoutubeUrl { kind, music: self.music }
+    }
+
+    fn host(&self) -> &'static str {
+        if self.music { "music.youtube.com" } else { "www.youtube.com" }
+    }
+
+    fn video_url(&self, id: &str) -> String {
+        format!("https://{}/watch?v={}", self.host(), id)
+    }
+
+    fn playlist_url(&self, list: &str) -> String {
+        format!("https://{}/playlist?list={}", self.host(), list)
+    }
+}
+
+/// Parses user input into a YouTube URL, accepting bare video IDs and `@handles`.
+pub fn parse(input: &str) -> Result<YoutubeUrl, String> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Err("Enter a URL".to_string());
+    }
+    if input.contains(char::is_whitespace) {
+        return Err("URL must not contain spaces".to_string());
+    }
+    if is_video_id(input) {
+        return Ok(YoutubeUrl { kind: UrlKind::Video { id: input.to_string() }, music: false });
+    }
+    if input.starts_with('@') && input.len() > 1 {
+        return Ok(YoutubeUrl { kind: UrlKind::Channel { path: input.to_string() }, music: false });
+    }
+
+    let without_scheme = input
+        .strip_prefix("https://")
+        .or_else(|| input.strip_prefix("http://"))
+        .unwrap_or(input);
+    let without_fragment = without_scheme.split('#').next().unwrap_or_default();
+    let (host_and_path, query) = match without_fragment.split_once('?') {
+        Some((before, query)) => (before, query),
+        None => (without_fragment, ""),
+    };
+    let (host, path) = match host_and_path.split_once('/') {
+        Some((host, path)) => (host, path.trim_end_matches('/')),
+        None => (host_and_path, ""),
+    };
+
+    let host = host.to_lowercase();
+    let host = host.strip_prefix("www.").unwrap_or(&host);
+    if !host.contains('.') {
+        return Err(format!("'{}' is not a valid URL or video ID", input));
+    }
+    if !YOUTUBE_HOSTS.contains(&host) {
+        return Err(format!("{} is not a YouTube address", host));
+    }
+
+    let music = host == "music.youtube.com";
+    let param = |name: &str| query_param(query, name);
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+
+    let kind = if host == "youtu.be" {
+        video_or_playlist(segments.first().copied(), param("list"))?
+    } else {
+        match segments.as_slice() {
+            ["watch"] => video_or_playlist(param("v").as_deref(), param("list"))?,
+            ["playlist"] => match param("list") {
+                Some(list) => UrlKind::Playlist { list },
+                None => return Err("Playlist URL is missing its list= parameter".to_string()),
+            },
+            ["shorts" | "live" | "embed" | "v", id] => video_or_playlist(Some(*id), param("list"))?,
+            [first, rest @ ..] if is_channel(first, rest) => UrlKind::Channel {
+                path: segments.join("/"),
+            },
+            _ => return Err("Unrecognized YouTube URL".to_string()),
+        }
+    };
+
+    Ok(YoutubeUrl { kind, music })
+}
+
+fn video_or_playlist(id: Option<&str>, list: Option<String>) -> Result<UrlKind, String> {
+    let id = match id {
+        Some(id) if is_video_id(id) => id.to_string(),
+        Some(id) => return Err(format!("'{}' is not a valid video ID", id)),
+        None => {
+            return match list {
+                Some(list) => Ok(UrlKind::Playlist { list }),
+                None => Err("URL is missing a video ID".to_string()),
+            }
+        }
+    };
+    Ok(match list {
+        // Auto-generated mixes (RD...) are endless, so treat them as plain videos
+        Some(list) if !list.starts_with("RD") => UrlKind::VideoInPlaylist { id, list },
+        _ => UrlKind::Video { id },
+    })
+}
+
+fn is_channel(first: &str, rest: &[&str]) -> bool {
+    let tab_ok = |tabs: &[&str]| tabs.is_empty() || (tabs.len() == 1 && CHANNEL_TABS.contains(&tabs[0]));
+    if first.starts_with('@') && first.len() > 1 {
+        return tab_ok(rest);
+    }
+    matches!(first, "channel" | "c" | "user") && !rest.is_empty() && tab_ok(&rest[1..])
+}
+
+/// Splits `@name/videos` or `channel/ID/videos` into the channel and its tab.
+fn split_channel_path(path: &str) -> (&str, Option<&str>) {
+    let base_segments = if path.starts_with('@') { 1 } else { 2 };
+    match path.match_indices('/').nth(base_segments - 1) {
+        Some((i, _)) => (&path[..i], Some(&path[i + 1..])),
+        None => (path, None),
+    }
+}
+
+fn query_param(query: &str, name: &str) -> Option<String> {
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, value)| *key == name && !value.is_empty())
+        .map(|(_, value)| value.to_string())
+}
+
+/// YouTube video IDs are 11 characters of base64url.
+fn is_video_id(id: &str) -> bool {
+    id.len() == 11 && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}